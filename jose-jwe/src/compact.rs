@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use core::{convert::Infallible, str::FromStr};
+
+use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use jose_b64::serde::Bytes;
+use jose_b64::stream::Error;
+
+use crate::{Flattened, General, Jwe, Recipient};
+
+/// Parses an optional (i.e. possibly empty) compact component.
+fn optional(s: &str) -> Result<Option<Bytes>, Error<serde_json::Error>> {
+    match s {
+        "" => Ok(None),
+        _ => Ok(Some(s.parse().map_err(|e: Error<Infallible>| e.cast())?)),
+    }
+}
+
+impl FromStr for Jwe {
+    type Err = Error<serde_json::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Flattened::from_str(s)?.into())
+    }
+}
+
+impl FromStr for General {
+    type Err = Error<serde_json::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Flattened::from_str(s)?.into())
+    }
+}
+
+impl FromStr for Flattened {
+    type Err = Error<serde_json::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter = s.split('.');
+
+        let prot = iter.next().ok_or(Error::Length)?;
+        let ekey = iter.next().ok_or(Error::Length)?;
+        let iv = iter.next().ok_or(Error::Length)?;
+        let ctxt = iter.next().ok_or(Error::Length)?;
+        let tag = iter.next().ok_or(Error::Length)?;
+        if iter.next().is_some() {
+            return Err(Error::Length);
+        }
+
+        Ok(Self {
+            protected: Some(prot.parse()?),
+            unprotected: None,
+            recipient: Recipient {
+                header: None,
+                encrypted_key: optional(ekey)?,
+            },
+            aad: None,
+            iv: optional(iv)?,
+            ciphertext: ctxt.parse().map_err(|e: Error<Infallible>| e.cast())?,
+            tag: optional(tag)?,
+        })
+    }
+}
+
+/// An error which prevents writing the JWE Compact Serialization.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompactError {
+    /// The JWE has a shared or per-recipient unprotected header.
+    Unprotected,
+
+    /// The JWE has Additional Authenticated Data.
+    Aad,
+}
+
+impl Display for CompactError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unprotected => f.write_str("compact JWE cannot have unprotected headers"),
+            Self::Aad => f.write_str("compact JWE cannot have AAD"),
+        }
+    }
+}
+
+impl Flattened {
+    /// Writes the JWE Compact Serialization.
+    ///
+    /// The compact form cannot represent unprotected headers or AAD, so this
+    /// fails when any of them is present.
+    pub fn to_compact(&self) -> Result<String, CompactError> {
+        if self.unprotected.is_some() || self.recipient.header.is_some() {
+            return Err(CompactError::Unprotected);
+        }
+
+        if self.aad.is_some() {
+            return Err(CompactError::Aad);
+        }
+
+        Ok(self.to_string())
+    }
+}
+
+/// Writes the JWE Compact Serialization.
+///
+/// Unprotected headers and AAD, which the compact form cannot represent, are
+/// left out. Use [`Flattened::to_compact`] to reject them instead.
+impl Display for Flattened {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fn encode(x: Option<&[u8]>) -> String {
+            x.map(Base64UrlUnpadded::encode_string).unwrap_or_default()
+        }
+
+        let prot = encode(self.protected.as_ref().map(|x| x.as_ref()));
+        let ekey = encode(self.recipient.encrypted_key.as_deref().map(|x| &x[..]));
+        let iv = encode(self.iv.as_deref().map(|x| &x[..]));
+        let ctxt = encode(Some(&self.ciphertext));
+        let tag = encode(self.tag.as_deref().map(|x| &x[..]));
+        write!(f, "{prot}.{ekey}.{iv}.{ctxt}.{tag}")
+    }
+}
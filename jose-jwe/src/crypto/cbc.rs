@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! AES CBC with HMAC SHA-2 content encryption as described in RFC 7518
+//! Section 5.2
+
+#![cfg(feature = "aes-cbc-hmac")]
+
+use alloc::vec::Vec;
+
+use aes::{Aes128, Aes192, Aes256};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use jose_jwa::Encryption;
+use jose_jwk::crypto::Error;
+use sha2::{Sha256, Sha384, Sha512};
+use subtle::ConstantTimeEq;
+
+/// Splits the CEK into the MAC key and the encryption key.
+fn split(enc: Encryption, cek: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    let len = match enc {
+        Encryption::A128CbcHs256 => 32,
+        Encryption::A192CbcHs384 => 48,
+        Encryption::A256CbcHs512 => 64,
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    if cek.len() != len {
+        return Err(Error::Invalid);
+    }
+
+    Ok(cek.split_at(len / 2))
+}
+
+fn mac<M: Mac + KeyInit>(
+    key: &[u8],
+    aad: &[u8],
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let al = (aad.len() as u64 * 8).to_be_bytes();
+
+    let mut mac = <M as Mac>::new_from_slice(key).map_err(|_| Error::Invalid)?;
+    mac.update(aad);
+    mac.update(iv);
+    mac.update(ciphertext);
+    mac.update(&al);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// Computes the authentication tag as described in RFC 7518 Section 5.2.2.1.
+///
+/// The tag is the HMAC output truncated to the size of the MAC key.
+fn tag(
+    enc: Encryption,
+    key: &[u8],
+    aad: &[u8],
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut tag = match enc {
+        Encryption::A128CbcHs256 => mac::<Hmac<Sha256>>(key, aad, iv, ciphertext)?,
+        Encryption::A192CbcHs384 => mac::<Hmac<Sha384>>(key, aad, iv, ciphertext)?,
+        _ => mac::<Hmac<Sha512>>(key, aad, iv, ciphertext)?,
+    };
+
+    tag.truncate(key.len());
+    Ok(tag)
+}
+
+/// Encrypts the plaintext, returning the ciphertext and authentication tag.
+pub(super) fn encrypt(
+    enc: Encryption,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let (mac_key, enc_key) = split(enc, cek)?;
+
+    let ciphertext = match enc {
+        Encryption::A128CbcHs256 => cbc::Encryptor::<Aes128>::new_from_slices(enc_key, iv)
+            .map_err(|_| Error::Invalid)?
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext),
+        Encryption::A192CbcHs384 => cbc::Encryptor::<Aes192>::new_from_slices(enc_key, iv)
+            .map_err(|_| Error::Invalid)?
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext),
+        _ => cbc::Encryptor::<Aes256>::new_from_slices(enc_key, iv)
+            .map_err(|_| Error::Invalid)?
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext),
+    };
+
+    let tag = tag(enc, mac_key, aad, iv, &ciphertext)?;
+    Ok((ciphertext, tag))
+}
+
+/// Verifies the authentication tag and decrypts the ciphertext.
+///
+/// The tag is compared in constant time before any decryption is attempted.
+pub(super) fn decrypt(
+    enc: Encryption,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let (mac_key, enc_key) = split(enc, cek)?;
+
+    let expected = self::tag(enc, mac_key, aad, iv, ciphertext)?;
+    if !bool::from(expected.ct_eq(tag)) {
+        return Err(Error::Invalid);
+    }
+
+    match enc {
+        Encryption::A128CbcHs256 => cbc::Decryptor::<Aes128>::new_from_slices(enc_key, iv)
+            .map_err(|_| Error::Invalid)?
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext),
+        Encryption::A192CbcHs384 => cbc::Decryptor::<Aes192>::new_from_slices(enc_key, iv)
+            .map_err(|_| Error::Invalid)?
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext),
+        _ => cbc::Decryptor::<Aes256>::new_from_slices(enc_key, iv)
+            .map_err(|_| Error::Invalid)?
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext),
+    }
+    .map_err(|_| Error::Invalid)
+}
@@ -0,0 +1,124 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! ECDH-ES key agreement as described in RFC 7518 Section 4.6
+
+#![cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+
+use alloc::{string::ToString, vec, vec::Vec};
+
+use jose_jwa::{Encryption, KeyManagement};
+use jose_jwk::crypto::Error;
+use jose_jwk::{Ec, Jwk, Key};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use super::Wrapped;
+use crate::Unprotected;
+
+/// Returns the length-prefixed form of a Concat KDF input.
+fn prefixed(out: &mut Vec<u8>, data: &[u8]) -> Result<(), Error> {
+    let len = u32::try_from(data.len()).map_err(|_| Error::Invalid)?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(data);
+    Ok(())
+}
+
+/// Derives the agreed upon key from the shared secret `z` using the Concat
+/// KDF as described in RFC 7518 Section 4.6.2.
+///
+/// For direct key agreement the result is the content encryption key;
+/// otherwise it is the key used to wrap the content encryption key.
+fn derive(
+    alg: KeyManagement,
+    enc: Encryption,
+    z: &[u8],
+    head: &Unprotected,
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let (id, len) = match alg {
+        KeyManagement::EcdhEs => (enc.to_string(), super::key_len(enc)),
+        KeyManagement::EcdhEsA128Kw => (alg.to_string(), 16),
+        KeyManagement::EcdhEsA192Kw => (alg.to_string(), 24),
+        KeyManagement::EcdhEsA256Kw => (alg.to_string(), 32),
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    let mut info = Vec::new();
+    prefixed(&mut info, id.as_bytes())?;
+    prefixed(
+        &mut info,
+        head.apu.as_ref().map(|x| &x[..]).unwrap_or_default(),
+    )?;
+    prefixed(
+        &mut info,
+        head.apv.as_ref().map(|x| &x[..]).unwrap_or_default(),
+    )?;
+    info.extend_from_slice(&(len as u32 * 8).to_be_bytes());
+
+    let mut key = Zeroizing::new(vec![0u8; len]);
+    concat_kdf::derive_key_into::<Sha256>(z, &info, &mut key).map_err(|_| Error::Invalid)?;
+    Ok(key)
+}
+
+/// Parses the `epk` header parameter.
+pub(super) fn epk<'a, T>(head: &'a Unprotected) -> Result<T, Error>
+where
+    T: TryFrom<&'a Ec, Error = Error>,
+{
+    match head.epk.as_ref().map(|x| &x.key) {
+        Some(Key::Ec(ec)) => ec.try_into(),
+        _ => Err(Error::Invalid),
+    }
+}
+
+/// Completes key agreement with the sender's shared secret `z`, emitting the
+/// ephemeral public key as the `epk` header parameter.
+pub(super) fn wrap(
+    alg: KeyManagement,
+    enc: Encryption,
+    z: &[u8],
+    epk: Ec,
+    head: &Unprotected,
+    out: &mut Wrapped,
+) -> Result<(), Error> {
+    let key = derive(alg, enc, z, head)?;
+
+    out.header.epk = Some(Jwk {
+        key: epk.into(),
+        prm: Default::default(),
+    });
+
+    match alg {
+        KeyManagement::EcdhEs => out.cek = key,
+
+        #[cfg(feature = "aes-kw")]
+        _ => out.encrypted_key = super::kw::wrap(&key, &out.cek)?,
+
+        #[cfg(not(feature = "aes-kw"))]
+        _ => return Err(Error::Unsupported),
+    }
+
+    Ok(())
+}
+
+/// Completes key agreement with the recipient's shared secret `z`.
+pub(super) fn unwrap(
+    alg: KeyManagement,
+    enc: Encryption,
+    z: &[u8],
+    head: &Unprotected,
+    encrypted_key: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let key = derive(alg, enc, z, head)?;
+
+    match alg {
+        KeyManagement::EcdhEs if encrypted_key.is_empty() => Ok(key),
+        KeyManagement::EcdhEs => Err(Error::Invalid),
+
+        #[cfg(feature = "aes-kw")]
+        _ => super::kw::unwrap(&key, encrypted_key),
+
+        #[cfg(not(feature = "aes-kw"))]
+        _ => Err(Error::Unsupported),
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! AES GCM content encryption as described in RFC 7518 Section 5.3
+
+#![cfg(feature = "aes-gcm")]
+
+use alloc::vec::Vec;
+
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::{Nonce, Tag};
+use aes_gcm::aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{AeadInPlace, AesGcm, KeyInit};
+use jose_jwa::Encryption;
+use jose_jwk::crypto::Error;
+
+fn seal<C: AeadInPlace + KeyInit>(
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    buf: &mut [u8],
+) -> Result<Vec<u8>, Error> {
+    let cipher = C::new_from_slice(cek).map_err(|_| Error::Invalid)?;
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::<C>::from_slice(iv), aad, buf)
+        .map_err(|_| Error::Invalid)?;
+
+    Ok(tag.to_vec())
+}
+
+fn open<C: AeadInPlace + KeyInit>(
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    buf: &mut [u8],
+    tag: &[u8],
+) -> Result<(), Error> {
+    let cipher = C::new_from_slice(cek).map_err(|_| Error::Invalid)?;
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::<C>::from_slice(iv),
+            aad,
+            buf,
+            Tag::<C>::from_slice(tag),
+        )
+        .map_err(|_| Error::Invalid)
+}
+
+/// Encrypts the plaintext, returning the ciphertext and authentication tag.
+pub(super) fn encrypt(
+    enc: Encryption,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    if iv.len() != 12 {
+        return Err(Error::Invalid);
+    }
+
+    let mut buf = plaintext.to_vec();
+    let tag = match enc {
+        Encryption::A128Gcm => seal::<AesGcm<Aes128, U12>>(cek, iv, aad, &mut buf)?,
+        Encryption::A192Gcm => seal::<AesGcm<Aes192, U12>>(cek, iv, aad, &mut buf)?,
+        Encryption::A256Gcm => seal::<AesGcm<Aes256, U12>>(cek, iv, aad, &mut buf)?,
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    Ok((buf, tag))
+}
+
+/// Verifies the authentication tag and decrypts the ciphertext.
+///
+/// The tag is compared in constant time by the `aes-gcm` crate.
+pub(super) fn decrypt(
+    enc: Encryption,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if iv.len() != 12 || tag.len() != 16 {
+        return Err(Error::Invalid);
+    }
+
+    let mut buf = ciphertext.to_vec();
+    match enc {
+        Encryption::A128Gcm => open::<AesGcm<Aes128, U12>>(cek, iv, aad, &mut buf, tag)?,
+        Encryption::A192Gcm => open::<AesGcm<Aes192, U12>>(cek, iv, aad, &mut buf, tag)?,
+        Encryption::A256Gcm => open::<AesGcm<Aes256, U12>>(cek, iv, aad, &mut buf, tag)?,
+        _ => return Err(Error::AlgMismatch),
+    }
+
+    Ok(buf)
+}
@@ -0,0 +1,57 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Key wrapping with AES GCM as described in RFC 7518 Section 4.7
+
+#![cfg(feature = "aes-gcm")]
+
+use alloc::{vec, vec::Vec};
+
+use jose_jwa::{Encryption, KeyManagement};
+use jose_jwk::crypto::Error;
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+use super::Wrapped;
+use crate::Unprotected;
+
+fn encryption(alg: KeyManagement) -> Result<Encryption, Error> {
+    match alg {
+        KeyManagement::A128GcmKw => Ok(Encryption::A128Gcm),
+        KeyManagement::A192GcmKw => Ok(Encryption::A192Gcm),
+        KeyManagement::A256GcmKw => Ok(Encryption::A256Gcm),
+        _ => Err(Error::AlgMismatch),
+    }
+}
+
+/// Encrypts the content encryption key, emitting the `iv` and `tag` header
+/// parameters.
+pub(super) fn wrap(
+    alg: KeyManagement,
+    kek: &[u8],
+    out: &mut Wrapped,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(), Error> {
+    let mut iv = vec![0u8; 12];
+    rng.fill_bytes(&mut iv);
+
+    let (encrypted_key, tag) = super::gcm::encrypt(encryption(alg)?, kek, &iv, &[], &out.cek)?;
+    out.encrypted_key = encrypted_key;
+    out.header.iv = Some(iv.into_boxed_slice().into());
+    out.header.tag = Some(tag.into_boxed_slice().into());
+    Ok(())
+}
+
+/// Decrypts the content encryption key using the `iv` and `tag` header
+/// parameters.
+pub(super) fn unwrap(
+    alg: KeyManagement,
+    kek: &[u8],
+    head: &Unprotected,
+    encrypted_key: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let iv = head.iv.as_ref().ok_or(Error::Invalid)?;
+    let tag = head.tag.as_ref().ok_or(Error::Invalid)?;
+    let cek = super::gcm::decrypt(encryption(alg)?, kek, iv, &[], encrypted_key, tag)?;
+    Ok(Zeroizing::new(cek))
+}
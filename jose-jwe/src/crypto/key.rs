@@ -0,0 +1,244 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Key management over the keys implemented by `jose-jwk`.
+
+use alloc::{vec, vec::Vec};
+
+use jose_jwa::{Encryption, KeyManagement, KeyManagement::*};
+use jose_jwk::crypto::{Error, Key, KeyInfo};
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+use super::key_len;
+use crate::Unprotected;
+
+/// A content encryption key prepared for a single recipient
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Wrapped {
+    /// The Content Encryption Key
+    pub cek: Zeroizing<Vec<u8>>,
+
+    /// The JWE Encrypted Key
+    ///
+    /// This is empty for direct encryption and direct key agreement.
+    pub encrypted_key: Vec<u8>,
+
+    /// Header parameters produced by the algorithm, such as `epk`, `iv`,
+    /// `tag`, `p2s` and `p2c`, which must be sent to the recipient.
+    pub header: Unprotected,
+}
+
+#[cfg(any(feature = "rsa", feature = "p256", feature = "p384", feature = "p521"))]
+fn secret<P, S>(kind: &jose_jwk::crypto::Kind<P, S>) -> Result<&S, Error> {
+    match kind {
+        jose_jwk::crypto::Kind::Secret(s) => Ok(s),
+        jose_jwk::crypto::Kind::Public(..) => Err(Error::NotPrivate),
+    }
+}
+
+/// Prepares a content encryption key for the recipient's key.
+///
+/// If `cek` is `None`, a random content encryption key is generated. Direct
+/// encryption (`dir`) and direct key agreement (`ECDH-ES`) determine the
+/// content encryption key themselves, so one may not be provided.
+///
+/// Algorithm parameters, such as `apu`, `apv`, `p2s` and `p2c`, are read
+/// from `head`.
+#[cfg_attr(
+    not(any(
+        feature = "pbes2",
+        feature = "p256",
+        feature = "p384",
+        feature = "p521"
+    )),
+    allow(unused_variables)
+)]
+pub fn wrap(
+    key: &Key,
+    alg: KeyManagement,
+    enc: Encryption,
+    cek: Option<&[u8]>,
+    head: &Unprotected,
+    mut rng: impl CryptoRngCore,
+) -> Result<Wrapped, Error> {
+    if !key.is_supported(&alg.into()) {
+        return Err(Error::AlgMismatch);
+    }
+
+    let cek = match (alg, cek) {
+        (Direct | EcdhEs, Some(..)) => return Err(Error::Invalid),
+        (Direct | EcdhEs, None) => Vec::new(),
+        (_, Some(cek)) if cek.len() == key_len(enc) => cek.to_vec(),
+        (_, Some(..)) => return Err(Error::Invalid),
+        (_, None) => {
+            let mut cek = vec![0u8; key_len(enc)];
+            rng.fill_bytes(&mut cek);
+            cek
+        }
+    };
+
+    let mut out = Wrapped {
+        cek: Zeroizing::new(cek),
+        encrypted_key: Vec::new(),
+        header: Unprotected::default(),
+    };
+
+    match (key, alg) {
+        (Key::Oct(k), Direct) if k.len() == key_len(enc) => out.cek = Zeroizing::new(k.to_vec()),
+        (Key::Oct(..), Direct) => return Err(Error::Invalid),
+
+        #[cfg(feature = "aes-kw")]
+        (Key::Oct(k), A128Kw | A192Kw | A256Kw) => {
+            out.encrypted_key = super::kw::wrap(k, &out.cek)?
+        }
+
+        #[cfg(feature = "aes-gcm")]
+        (Key::Oct(k), A128GcmKw | A192GcmKw | A256GcmKw) => {
+            super::gcmkw::wrap(alg, k, &mut out, &mut rng)?
+        }
+
+        #[cfg(feature = "pbes2")]
+        (Key::Oct(k), Pbes2Hs256A128Kw | Pbes2Hs384A192Kw | Pbes2Hs512A256Kw) => {
+            super::pbes2::wrap(alg, k, head, &mut out, &mut rng)?
+        }
+
+        #[cfg(feature = "rsa")]
+        (Key::Rsa(kind), _) => {
+            let key = match kind {
+                jose_jwk::crypto::Kind::Public(key) => key,
+                jose_jwk::crypto::Kind::Secret(key) => key.as_ref(),
+            };
+
+            out.encrypted_key = super::rsa::wrap(key, alg, &out.cek, &mut rng)?;
+        }
+
+        #[cfg(feature = "p256")]
+        (Key::P256(kind), _) => {
+            let key = match kind {
+                jose_jwk::crypto::Kind::Public(key) => *key,
+                jose_jwk::crypto::Kind::Secret(key) => key.public_key(),
+            };
+
+            let esk = p256::ecdh::EphemeralSecret::random(&mut rng);
+            let z = esk.diffie_hellman(&key);
+            let epk = esk.public_key().into();
+            super::ecdh::wrap(alg, enc, z.raw_secret_bytes(), epk, head, &mut out)?;
+        }
+
+        #[cfg(feature = "p384")]
+        (Key::P384(kind), _) => {
+            let key = match kind {
+                jose_jwk::crypto::Kind::Public(key) => *key,
+                jose_jwk::crypto::Kind::Secret(key) => key.public_key(),
+            };
+
+            let esk = p384::ecdh::EphemeralSecret::random(&mut rng);
+            let z = esk.diffie_hellman(&key);
+            let epk = esk.public_key().into();
+            super::ecdh::wrap(alg, enc, z.raw_secret_bytes(), epk, head, &mut out)?;
+        }
+
+        #[cfg(feature = "p521")]
+        (Key::P521(kind), _) => {
+            let key = match kind {
+                jose_jwk::crypto::Kind::Public(key) => *key,
+                jose_jwk::crypto::Kind::Secret(key) => key.public_key(),
+            };
+
+            let esk = p521::ecdh::EphemeralSecret::random(&mut rng);
+            let z = esk.diffie_hellman(&key);
+            let epk = esk.public_key().into();
+            super::ecdh::wrap(alg, enc, z.raw_secret_bytes(), epk, head, &mut out)?;
+        }
+
+        _ => return Err(Error::Unsupported),
+    }
+
+    Ok(out)
+}
+
+/// Recovers the content encryption key using the recipient's key.
+///
+/// The `head` parameter should contain all of the header parameters that
+/// apply to the recipient. The random number generator is used for RSA
+/// blinding.
+#[cfg_attr(
+    not(all(
+        feature = "rsa",
+        any(
+            feature = "aes-gcm",
+            feature = "pbes2",
+            feature = "p256",
+            feature = "p384",
+            feature = "p521"
+        )
+    )),
+    allow(unused_variables)
+)]
+#[cfg_attr(not(feature = "rsa"), allow(unused_mut))]
+pub fn unwrap(
+    key: &Key,
+    alg: KeyManagement,
+    enc: Encryption,
+    head: &Unprotected,
+    encrypted_key: &[u8],
+    mut rng: impl CryptoRngCore,
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    if !key.is_supported(&alg.into()) {
+        return Err(Error::AlgMismatch);
+    }
+
+    let cek = match (key, alg) {
+        (Key::Oct(k), Direct) if encrypted_key.is_empty() => Zeroizing::new(k.to_vec()),
+        (Key::Oct(..), Direct) => return Err(Error::Invalid),
+
+        #[cfg(feature = "aes-kw")]
+        (Key::Oct(k), A128Kw | A192Kw | A256Kw) => super::kw::unwrap(k, encrypted_key)?,
+
+        #[cfg(feature = "aes-gcm")]
+        (Key::Oct(k), A128GcmKw | A192GcmKw | A256GcmKw) => {
+            super::gcmkw::unwrap(alg, k, head, encrypted_key)?
+        }
+
+        #[cfg(feature = "pbes2")]
+        (Key::Oct(k), Pbes2Hs256A128Kw | Pbes2Hs384A192Kw | Pbes2Hs512A256Kw) => {
+            super::pbes2::unwrap(alg, k, head, encrypted_key)?
+        }
+
+        #[cfg(feature = "rsa")]
+        (Key::Rsa(kind), _) => {
+            super::rsa::unwrap(secret(kind)?, alg, encrypted_key, key_len(enc), &mut rng)?
+        }
+
+        #[cfg(feature = "p256")]
+        (Key::P256(kind), _) => {
+            let epk: p256::PublicKey = super::ecdh::epk(head)?;
+            let z = p256::ecdh::diffie_hellman(secret(kind)?.to_nonzero_scalar(), epk.as_affine());
+            super::ecdh::unwrap(alg, enc, z.raw_secret_bytes(), head, encrypted_key)?
+        }
+
+        #[cfg(feature = "p384")]
+        (Key::P384(kind), _) => {
+            let epk: p384::PublicKey = super::ecdh::epk(head)?;
+            let z = p384::ecdh::diffie_hellman(secret(kind)?.to_nonzero_scalar(), epk.as_affine());
+            super::ecdh::unwrap(alg, enc, z.raw_secret_bytes(), head, encrypted_key)?
+        }
+
+        #[cfg(feature = "p521")]
+        (Key::P521(kind), _) => {
+            let epk: p521::PublicKey = super::ecdh::epk(head)?;
+            let z = p521::ecdh::diffie_hellman(secret(kind)?.to_nonzero_scalar(), epk.as_affine());
+            super::ecdh::unwrap(alg, enc, z.raw_secret_bytes(), head, encrypted_key)?
+        }
+
+        _ => return Err(Error::Unsupported),
+    };
+
+    if cek.len() != key_len(enc) {
+        return Err(Error::Invalid);
+    }
+
+    Ok(cek)
+}
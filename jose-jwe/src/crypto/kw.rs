@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! AES Key Wrap as described in RFC 7518 Section 4.4
+
+#![cfg(feature = "aes-kw")]
+
+use alloc::vec::Vec;
+
+use aes::cipher::generic_array::GenericArray;
+use aes_kw::{KekAes128, KekAes192, KekAes256};
+use jose_jwk::crypto::Error;
+use zeroize::Zeroizing;
+
+/// Wraps the content encryption key with a 128, 192 or 256-bit key.
+pub(super) fn wrap(kek: &[u8], cek: &[u8]) -> Result<Vec<u8>, Error> {
+    let wrapped = match kek.len() {
+        16 => KekAes128::new(GenericArray::from_slice(kek)).wrap_vec(cek),
+        24 => KekAes192::new(GenericArray::from_slice(kek)).wrap_vec(cek),
+        32 => KekAes256::new(GenericArray::from_slice(kek)).wrap_vec(cek),
+        _ => return Err(Error::Invalid),
+    };
+
+    wrapped.map_err(|_| Error::Invalid)
+}
+
+/// Unwraps the content encryption key with a 128, 192 or 256-bit key.
+pub(super) fn unwrap(kek: &[u8], encrypted_key: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let unwrapped = match kek.len() {
+        16 => KekAes128::new(GenericArray::from_slice(kek)).unwrap_vec(encrypted_key),
+        24 => KekAes192::new(GenericArray::from_slice(kek)).unwrap_vec(encrypted_key),
+        32 => KekAes256::new(GenericArray::from_slice(kek)).unwrap_vec(encrypted_key),
+        _ => return Err(Error::Invalid),
+    };
+
+    unwrapped.map(Zeroizing::new).map_err(|_| Error::Invalid)
+}
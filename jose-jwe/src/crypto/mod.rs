@@ -0,0 +1,293 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! JWE Cryptographic Implementation
+
+mod cbc;
+mod ecdh;
+mod gcm;
+mod gcmkw;
+mod key;
+mod kw;
+mod oneshot;
+mod pbes2;
+mod rsa;
+
+pub use key::{unwrap, wrap, Wrapped};
+pub use oneshot::{open, seal, seal_all};
+
+use alloc::{vec, vec::Vec};
+use core::slice;
+
+use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use jose_b64::serde::Json;
+use jose_jwa::Encryption;
+use jose_jwk::{crypto::Error, Thumbprint};
+use rand_core::CryptoRngCore;
+
+use crate::{Flattened, General, Jwe, Protected, Recipient, Unprotected};
+
+/// The output of content encryption
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Encrypted {
+    /// The JWE Initialization Vector
+    pub iv: Vec<u8>,
+
+    /// The JWE Ciphertext
+    pub ciphertext: Vec<u8>,
+
+    /// The JWE Authentication Tag
+    pub tag: Vec<u8>,
+}
+
+/// Returns the length of the content encryption key, in bytes.
+pub fn key_len(enc: Encryption) -> usize {
+    match enc {
+        Encryption::A128CbcHs256 => 32,
+        Encryption::A192CbcHs384 => 48,
+        Encryption::A256CbcHs512 => 64,
+        Encryption::A128Gcm => 16,
+        Encryption::A192Gcm => 24,
+        Encryption::A256Gcm => 32,
+        #[allow(unreachable_patterns)]
+        _ => 0,
+    }
+}
+
+fn iv_len(enc: Encryption) -> usize {
+    match enc {
+        Encryption::A128Gcm | Encryption::A192Gcm | Encryption::A256Gcm => 12,
+        _ => 16,
+    }
+}
+
+/// Computes the Additional Authenticated Data as described in RFC 7516
+/// Section 5.1 Step 14.
+///
+/// This is the ASCII of the base64url-encoded protected header, followed by
+/// a period and the base64url-encoded JWE AAD value, if any.
+pub fn aad(prot: Option<&Json<Protected>>, aad: Option<&[u8]>) -> Vec<u8> {
+    let mut out = match prot {
+        Some(prot) => Base64UrlUnpadded::encode_string(prot.as_ref()).into_bytes(),
+        None => Vec::new(),
+    };
+
+    if let Some(aad) = aad {
+        out.push(b'.');
+        out.extend(Base64UrlUnpadded::encode_string(aad).into_bytes());
+    }
+
+    out
+}
+
+/// Encrypts the plaintext using the content encryption key.
+///
+/// A fresh initialization vector is generated using the random number
+/// generator. The `aad` parameter should be computed using [`aad()`].
+#[cfg_attr(
+    not(any(feature = "aes-gcm", feature = "aes-cbc-hmac")),
+    allow(unused_variables)
+)]
+pub fn encrypt(
+    enc: Encryption,
+    cek: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+    mut rng: impl CryptoRngCore,
+) -> Result<Encrypted, Error> {
+    let mut iv = vec![0u8; iv_len(enc)];
+    rng.fill_bytes(&mut iv);
+
+    let (ciphertext, tag) = match enc {
+        #[cfg(feature = "aes-gcm")]
+        Encryption::A128Gcm | Encryption::A192Gcm | Encryption::A256Gcm => {
+            gcm::encrypt(enc, cek, &iv, aad, plaintext)
+        }
+
+        #[cfg(feature = "aes-cbc-hmac")]
+        Encryption::A128CbcHs256 | Encryption::A192CbcHs384 | Encryption::A256CbcHs512 => {
+            cbc::encrypt(enc, cek, &iv, aad, plaintext)
+        }
+
+        _ => Err(Error::Unsupported),
+    }?;
+
+    Ok(Encrypted {
+        iv,
+        ciphertext,
+        tag,
+    })
+}
+
+/// Verifies the authentication tag and decrypts the ciphertext.
+///
+/// The authentication tag is always compared in constant time.
+#[cfg_attr(
+    not(any(feature = "aes-gcm", feature = "aes-cbc-hmac")),
+    allow(unused_variables)
+)]
+pub fn decrypt(
+    enc: Encryption,
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>, Error> {
+    match enc {
+        #[cfg(feature = "aes-gcm")]
+        Encryption::A128Gcm | Encryption::A192Gcm | Encryption::A256Gcm => {
+            gcm::decrypt(enc, cek, iv, aad, ciphertext, tag)
+        }
+
+        #[cfg(feature = "aes-cbc-hmac")]
+        Encryption::A128CbcHs256 | Encryption::A192CbcHs384 | Encryption::A256CbcHs512 => {
+            cbc::decrypt(enc, cek, iv, aad, ciphertext, tag)
+        }
+
+        _ => Err(Error::Unsupported),
+    }
+}
+
+fn pick<T: Clone>(a: &Option<T>, b: &Option<T>) -> Result<Option<T>, Error> {
+    match (a, b) {
+        (Some(..), Some(..)) => Err(Error::Invalid),
+        (x, None) | (None, x) => Ok(x.clone()),
+    }
+}
+
+/// Combines two headers whose parameter names must be disjoint.
+fn merge(a: &Unprotected, b: &Unprotected) -> Result<Unprotected, Error> {
+    Ok(Unprotected {
+        alg: pick(&a.alg, &b.alg)?,
+        enc: pick(&a.enc, &b.enc)?,
+        zip: pick(&a.zip, &b.zip)?,
+        jwk: pick(&a.jwk, &b.jwk)?,
+        kid: pick(&a.kid, &b.kid)?,
+        x5c: pick(&a.x5c, &b.x5c)?,
+        x5t: Thumbprint {
+            s1: pick(&a.x5t.s1, &b.x5t.s1)?,
+            s256: pick(&a.x5t.s256, &b.x5t.s256)?,
+        },
+        typ: pick(&a.typ, &b.typ)?,
+        cty: pick(&a.cty, &b.cty)?,
+        epk: pick(&a.epk, &b.epk)?,
+        apu: pick(&a.apu, &b.apu)?,
+        apv: pick(&a.apv, &b.apv)?,
+        iv: pick(&a.iv, &b.iv)?,
+        tag: pick(&a.tag, &b.tag)?,
+        p2s: pick(&a.p2s, &b.p2s)?,
+        p2c: pick(&a.p2c, &b.p2c)?,
+        crit: pick(&a.crit, &b.crit)?,
+    })
+}
+
+/// Computes the JOSE Header, which is the union of the protected header, the
+/// shared unprotected header and the per-recipient unprotected header.
+///
+/// As required by RFC 7516 Section 7.2.1, the headers must be disjoint.
+/// Compression is not supported. No extensions are understood, so any
+/// critical header parameter (RFC 7516 Section 4.1.13) is rejected, as is
+/// `crit` outside of the protected header.
+fn header(
+    prot: Option<&Protected>,
+    shared: Option<&Unprotected>,
+    recipient: Option<&Unprotected>,
+) -> Result<Unprotected, Error> {
+    if prot.map_or(false, |x| x.crit.is_some() || x.oth.crit.is_some()) {
+        return Err(Error::Unsupported);
+    }
+
+    let mut head = prot.map(|x| x.oth.clone()).unwrap_or_default();
+
+    for other in [shared, recipient].into_iter().flatten() {
+        head = merge(&head, other)?;
+    }
+
+    if head.zip.is_some() || head.crit.is_some() {
+        return Err(Error::Unsupported);
+    }
+
+    Ok(head)
+}
+
+/// A borrowed view of the parts of a JWE.
+struct Parts<'a> {
+    protected: Option<&'a Json<Protected>>,
+    unprotected: Option<&'a Unprotected>,
+    recipients: &'a [Recipient],
+    aad: Option<&'a [u8]>,
+    iv: Option<&'a [u8]>,
+    ciphertext: &'a [u8],
+    tag: Option<&'a [u8]>,
+}
+
+impl<'a> From<&'a General> for Parts<'a> {
+    fn from(value: &'a General) -> Self {
+        Self {
+            protected: value.protected.as_ref(),
+            unprotected: value.unprotected.as_ref(),
+            recipients: &value.recipients,
+            aad: value.aad.as_ref().map(|x| &x[..]),
+            iv: value.iv.as_ref().map(|x| &x[..]),
+            ciphertext: &value.ciphertext,
+            tag: value.tag.as_ref().map(|x| &x[..]),
+        }
+    }
+}
+
+impl<'a> From<&'a Flattened> for Parts<'a> {
+    fn from(value: &'a Flattened) -> Self {
+        Self {
+            protected: value.protected.as_ref(),
+            unprotected: value.unprotected.as_ref(),
+            recipients: slice::from_ref(&value.recipient),
+            aad: value.aad.as_ref().map(|x| &x[..]),
+            iv: value.iv.as_ref().map(|x| &x[..]),
+            ciphertext: &value.ciphertext,
+            tag: value.tag.as_ref().map(|x| &x[..]),
+        }
+    }
+}
+
+impl<'a> From<&'a Jwe> for Parts<'a> {
+    fn from(value: &'a Jwe) -> Self {
+        match value {
+            Jwe::General(general) => general.into(),
+            Jwe::Flattened(flattened) => flattened.into(),
+        }
+    }
+}
+
+impl Parts<'_> {
+    /// Computes the JOSE Header for one of the recipients.
+    fn header(&self, recipient: Option<&Unprotected>) -> Result<Unprotected, Error> {
+        header(self.protected.map(|x| &**x), self.unprotected, recipient)
+    }
+
+    fn decrypt(&self, enc: Encryption, cek: &[u8]) -> Result<Vec<u8>, Error> {
+        let aad = aad(self.protected, self.aad);
+        let iv = self.iv.ok_or(Error::Invalid)?;
+        let tag = self.tag.ok_or(Error::Invalid)?;
+        decrypt(enc, cek, iv, &aad, self.ciphertext, tag)
+    }
+}
+
+impl General {
+    /// Decrypts the content using the content encryption key.
+    pub fn decrypt(&self, cek: &[u8]) -> Result<Vec<u8>, Error> {
+        let parts = Parts::from(self);
+        let enc = parts.header(None)?.enc.ok_or(Error::Invalid)?;
+        parts.decrypt(enc, cek)
+    }
+}
+
+impl Flattened {
+    /// Decrypts the content using the content encryption key.
+    pub fn decrypt(&self, cek: &[u8]) -> Result<Vec<u8>, Error> {
+        let parts = Parts::from(self);
+        let enc = parts.header(None)?.enc.ok_or(Error::Invalid)?;
+        parts.decrypt(enc, cek)
+    }
+}
@@ -0,0 +1,204 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! One-shot encryption and decryption with the keys implemented by
+//! `jose-jwk`.
+
+use alloc::vec::Vec;
+
+use jose_b64::serde::{Bytes, Json};
+use jose_jwa::{Encryption, KeyManagement};
+use jose_jwk::crypto::{Error, Key};
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+use super::{aad, encrypt, header, merge, unwrap, wrap, Encrypted, Parts, Wrapped};
+use crate::{Flattened, General, Jwe, Protected, Recipient, Unprotected};
+
+/// Tests whether the key management algorithm produced header parameters.
+fn produced(wrapped: &Wrapped) -> bool {
+    let head = &wrapped.header;
+    head.epk.is_some()
+        || head.iv.is_some()
+        || head.tag.is_some()
+        || head.p2s.is_some()
+        || head.p2c.is_some()
+}
+
+/// Returns the JWE Encrypted Key, which is absent when empty.
+///
+/// See RFC 7516 Section 7.2.1.
+fn encrypted_key(wrapped: &Wrapped) -> Option<Bytes> {
+    match wrapped.encrypted_key.is_empty() {
+        true => None,
+        false => Some(wrapped.encrypted_key.clone().into_boxed_slice().into()),
+    }
+}
+
+/// Creates the recipient, adding the produced parameters to its header.
+fn recipient(head: Option<Unprotected>, wrapped: &Wrapped) -> Result<Recipient, Error> {
+    let header = match head {
+        Some(head) => Some(merge(&head, &wrapped.header)?),
+        None if produced(wrapped) => Some(wrapped.header.clone()),
+        None => None,
+    };
+
+    Ok(Recipient {
+        header,
+        encrypted_key: encrypted_key(wrapped),
+    })
+}
+
+/// Serializes the protected header and encrypts the plaintext.
+fn content(
+    prot: Protected,
+    cek: &[u8],
+    plaintext: &[u8],
+    rng: impl CryptoRngCore,
+) -> Result<(Json<Protected>, Encrypted), Error> {
+    let enc = prot.oth.enc.ok_or(Error::Invalid)?;
+    let prot = Json::new(prot).map_err(|_| Error::Invalid)?;
+    let aad = aad(Some(&prot), None);
+    let encrypted = encrypt(enc, cek, &aad, plaintext, rng)?;
+    Ok((prot, encrypted))
+}
+
+/// Encrypts the plaintext to a single recipient, creating a flattened JWE.
+///
+/// The `enc` parameter must appear in the protected header. The `alg`
+/// parameter may appear in either header. Parameters produced by the key
+/// management algorithm are added to the per-recipient header when one is
+/// given and to the protected header otherwise, so that the result can use
+/// the compact serialization.
+pub fn seal(
+    key: &Key,
+    mut prot: Protected,
+    head: Option<Unprotected>,
+    plaintext: &[u8],
+    mut rng: impl CryptoRngCore,
+) -> Result<Jwe, Error> {
+    let all = header(Some(&prot), None, head.as_ref())?;
+    let alg = all.alg.ok_or(Error::Invalid)?;
+    let enc = prot.oth.enc.ok_or(Error::Invalid)?;
+    let wrapped = wrap(key, alg, enc, None, &all, &mut rng)?;
+
+    let recipient = match head {
+        Some(head) => recipient(Some(head), &wrapped)?,
+        None => {
+            prot.oth = merge(&prot.oth, &wrapped.header)?;
+            Recipient {
+                header: None,
+                encrypted_key: encrypted_key(&wrapped),
+            }
+        }
+    };
+
+    let (prot, encrypted) = content(prot, &wrapped.cek, plaintext, rng)?;
+
+    Ok(Jwe::Flattened(Flattened {
+        protected: Some(prot),
+        unprotected: None,
+        recipient,
+        aad: None,
+        iv: Some(encrypted.iv.into_boxed_slice().into()),
+        ciphertext: encrypted.ciphertext.into_boxed_slice().into(),
+        tag: Some(encrypted.tag.into_boxed_slice().into()),
+    }))
+}
+
+/// Encrypts the plaintext to each of the recipients, creating a general JWE.
+///
+/// The `enc` parameter must appear in the protected header. The `alg`
+/// parameter may appear in either header. At least one recipient must be
+/// provided and direct encryption or direct key agreement may only be used
+/// with a single recipient.
+pub fn seal_all<'a, I>(
+    recipients: I,
+    prot: Protected,
+    plaintext: &[u8],
+    mut rng: impl CryptoRngCore,
+) -> Result<Jwe, Error>
+where
+    I: IntoIterator<Item = (&'a Key, Option<Unprotected>)>,
+{
+    let enc = prot.oth.enc.ok_or(Error::Invalid)?;
+    let recipients: Vec<_> = recipients.into_iter().collect();
+    let count = recipients.len();
+
+    let mut cek: Option<Zeroizing<Vec<u8>>> = None;
+    let mut all = Vec::with_capacity(count);
+    for (key, head) in recipients {
+        let params = header(Some(&prot), None, head.as_ref())?;
+        let alg = params.alg.ok_or(Error::Invalid)?;
+
+        let direct = matches!(alg, KeyManagement::Direct | KeyManagement::EcdhEs);
+        if direct && count > 1 {
+            return Err(Error::Invalid);
+        }
+
+        let wrapped = wrap(
+            key,
+            alg,
+            enc,
+            cek.as_ref().map(|x| &x[..]),
+            &params,
+            &mut rng,
+        )?;
+        all.push(recipient(head, &wrapped)?);
+        cek.get_or_insert(wrapped.cek);
+    }
+
+    let cek = cek.ok_or(Error::Invalid)?;
+    let (prot, encrypted) = content(prot, &cek, plaintext, rng)?;
+
+    Ok(Jwe::General(General {
+        protected: Some(prot),
+        unprotected: None,
+        recipients: all,
+        aad: None,
+        iv: Some(encrypted.iv.into_boxed_slice().into()),
+        ciphertext: encrypted.ciphertext.into_boxed_slice().into(),
+        tag: Some(encrypted.tag.into_boxed_slice().into()),
+    }))
+}
+
+/// Decrypts a JWE with the recipient's key.
+///
+/// Each recipient is tried in turn and the plaintext is returned from the
+/// first one for which both the content encryption key and the content can
+/// be decrypted. Recipients whose key management algorithm is not in `algs`
+/// or whose content encryption algorithm is not in `encs` are rejected with
+/// [`Error::Forbidden`]. No extensions are understood, so a JWE with critical
+/// header parameters (RFC 7516 Section 4.1.13) is rejected with
+/// [`Error::Unsupported`].
+pub fn open(
+    key: &Key,
+    jwe: &Jwe,
+    algs: &[KeyManagement],
+    encs: &[Encryption],
+    mut rng: impl CryptoRngCore,
+) -> Result<Vec<u8>, Error> {
+    let parts = Parts::from(jwe);
+
+    let mut last = Error::default();
+    for recipient in parts.recipients {
+        let res = parts.header(recipient.header.as_ref()).and_then(|head| {
+            let alg = head.alg.ok_or(Error::Invalid)?;
+            let enc = head.enc.ok_or(Error::Invalid)?;
+            if !algs.contains(&alg) || !encs.contains(&enc) {
+                return Err(Error::Forbidden);
+            }
+
+            let ek = recipient.encrypted_key.as_ref().map(|x| &x[..]);
+            let cek = unwrap(key, alg, enc, &head, ek.unwrap_or_default(), &mut rng)?;
+            parts.decrypt(enc, &cek)
+        });
+
+        match res {
+            Ok(plaintext) => return Ok(plaintext),
+            Err(e) => last = e,
+        }
+    }
+
+    Err(last)
+}
@@ -0,0 +1,105 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! PBES2 key encryption as described in RFC 7518 Section 4.8
+
+#![cfg(feature = "pbes2")]
+
+use alloc::{string::ToString, vec, vec::Vec};
+
+use jose_jwa::KeyManagement;
+use jose_jwk::crypto::Error;
+use pbkdf2::pbkdf2_hmac;
+use rand_core::CryptoRngCore;
+use sha2::{Sha256, Sha384, Sha512};
+use zeroize::Zeroizing;
+
+use super::Wrapped;
+use crate::Unprotected;
+
+/// The iteration count used when the `p2c` header parameter is absent.
+const P2C: u32 = 600_000;
+
+/// The accepted range of iteration counts.
+///
+/// The lower bound follows RFC 7518 Section 4.8.1.2. The upper bound stops
+/// an attacker from forcing an arbitrary amount of work on the recipient.
+const P2C_RANGE: core::ops::RangeInclusive<u32> = 1000..=1_000_000;
+
+/// Derives the key encryption key from the password.
+fn derive(
+    alg: KeyManagement,
+    password: &[u8],
+    p2s: &[u8],
+    p2c: u32,
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let len = match alg {
+        KeyManagement::Pbes2Hs256A128Kw => 16,
+        KeyManagement::Pbes2Hs384A192Kw => 24,
+        KeyManagement::Pbes2Hs512A256Kw => 32,
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    // RFC 7518 Section 4.8.1.1
+    if p2s.len() < 8 || !P2C_RANGE.contains(&p2c) {
+        return Err(Error::Invalid);
+    }
+
+    let mut salt = alg.to_string().into_bytes();
+    salt.push(0);
+    salt.extend_from_slice(p2s);
+
+    let mut kek = Zeroizing::new(vec![0u8; len]);
+    match alg {
+        KeyManagement::Pbes2Hs256A128Kw => pbkdf2_hmac::<Sha256>(password, &salt, p2c, &mut kek),
+        KeyManagement::Pbes2Hs384A192Kw => pbkdf2_hmac::<Sha384>(password, &salt, p2c, &mut kek),
+        _ => pbkdf2_hmac::<Sha512>(password, &salt, p2c, &mut kek),
+    }
+
+    Ok(kek)
+}
+
+/// Wraps the content encryption key with a key derived from the password.
+///
+/// The `p2s` and `p2c` header parameters are used when present. Otherwise,
+/// a random salt and a default iteration count are chosen and emitted.
+pub(super) fn wrap(
+    alg: KeyManagement,
+    password: &[u8],
+    head: &Unprotected,
+    out: &mut Wrapped,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(), Error> {
+    let p2s = match head.p2s.as_ref() {
+        Some(p2s) => p2s.to_vec(),
+        None => {
+            let mut p2s = vec![0u8; 16];
+            rng.fill_bytes(&mut p2s);
+            out.header.p2s = Some(p2s.clone().into_boxed_slice().into());
+            p2s
+        }
+    };
+
+    let p2c = match head.p2c {
+        Some(p2c) => p2c,
+        None => *out.header.p2c.insert(P2C),
+    };
+
+    let kek = derive(alg, password, &p2s, p2c)?;
+    out.encrypted_key = super::kw::wrap(&kek, &out.cek)?;
+    Ok(())
+}
+
+/// Unwraps the content encryption key using the `p2s` and `p2c` header
+/// parameters.
+pub(super) fn unwrap(
+    alg: KeyManagement,
+    password: &[u8],
+    head: &Unprotected,
+    encrypted_key: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let p2s = head.p2s.as_ref().ok_or(Error::Invalid)?;
+    let p2c = head.p2c.ok_or(Error::Invalid)?;
+    let kek = derive(alg, password, p2s, p2c)?;
+    super::kw::unwrap(&kek, encrypted_key)
+}
@@ -0,0 +1,65 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! RSA key encryption as described in RFC 7518 Sections 4.2 and 4.3
+
+#![cfg(feature = "rsa")]
+
+use alloc::{vec, vec::Vec};
+
+use jose_jwa::KeyManagement;
+use jose_jwk::crypto::Error;
+use rand_core::CryptoRngCore;
+use rsa::{Oaep, Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey};
+use sha1::Sha1;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/// Encrypts the content encryption key to the public key.
+pub(super) fn wrap(
+    key: &RsaPublicKey,
+    alg: KeyManagement,
+    cek: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<Vec<u8>, Error> {
+    let encrypted = match alg {
+        KeyManagement::Rsa1_5 => key.encrypt(rng, Pkcs1v15Encrypt, cek),
+        KeyManagement::RsaOaep => key.encrypt(rng, Oaep::new::<Sha1>(), cek),
+        KeyManagement::RsaOaep256 => key.encrypt(rng, Oaep::new::<Sha256>(), cek),
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    encrypted.map_err(|_| Error::Invalid)
+}
+
+/// Decrypts the content encryption key with the private key.
+///
+/// As recommended by RFC 7516 Section 11.5, a failure to decrypt an
+/// `RSA1_5` encrypted key is not reported. Instead, a random key of the
+/// expected length is returned so that the failure is only detected during
+/// content decryption.
+pub(super) fn unwrap(
+    key: &RsaPrivateKey,
+    alg: KeyManagement,
+    encrypted_key: &[u8],
+    len: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let decrypted = match alg {
+        KeyManagement::Rsa1_5 => {
+            let mut random = Zeroizing::new(vec![0u8; len]);
+            rng.fill_bytes(&mut random);
+
+            return match key.decrypt_blinded(rng, Pkcs1v15Encrypt, encrypted_key) {
+                Ok(cek) if cek.len() == len => Ok(Zeroizing::new(cek)),
+                _ => Ok(random),
+            };
+        }
+
+        KeyManagement::RsaOaep => key.decrypt_blinded(rng, Oaep::new::<Sha1>(), encrypted_key),
+        KeyManagement::RsaOaep256 => key.decrypt_blinded(rng, Oaep::new::<Sha256>(), encrypted_key),
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    decrypted.map(Zeroizing::new).map_err(|_| Error::Invalid)
+}
@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::vec::Vec;
+use alloc::{boxed::Box, string::String};
+
+use jose_b64::base64ct::Base64;
+use jose_b64::serde::Bytes;
+use jose_jwa::{Encryption, KeyManagement};
+use jose_jwk::{Jwk, Thumbprint};
+use serde::{Deserialize, Serialize};
+
+/// The JWE Protected Header
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Protected {
+    /// RFC 7516 Section 4.1.13
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub crit: Option<Vec<String>>,
+
+    /// Other values that may appear in the protected header.
+    #[serde(flatten)]
+    pub oth: Unprotected,
+}
+
+/// The JWE Shared Unprotected Header or Per-Recipient Unprotected Header
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Unprotected {
+    /// RFC 7516 Section 4.1.1
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub alg: Option<KeyManagement>,
+
+    /// RFC 7516 Section 4.1.2
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub enc: Option<Encryption>,
+
+    /// RFC 7516 Section 4.1.3
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub zip: Option<String>,
+
+    /// RFC 7516 Section 4.1.5
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub jwk: Option<Jwk>,
+
+    /// RFC 7516 Section 4.1.6
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub kid: Option<String>,
+
+    /// RFC 7516 Section 4.1.8
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub x5c: Option<Vec<Bytes<Box<[u8]>, Base64>>>, // base64, not base64url
+
+    /// RFC 7516 Section 4.1.9-10
+    #[serde(flatten)]
+    pub x5t: Thumbprint,
+
+    /// RFC 7516 Section 4.1.11
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub typ: Option<String>,
+
+    /// RFC 7516 Section 4.1.12
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cty: Option<String>,
+
+    /// RFC 7518 Section 4.6.1.1
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub epk: Option<Jwk>,
+
+    /// RFC 7518 Section 4.6.1.2
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub apu: Option<Bytes>,
+
+    /// RFC 7518 Section 4.6.1.3
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub apv: Option<Bytes>,
+
+    /// RFC 7518 Section 4.7.1.1
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iv: Option<Bytes>,
+
+    /// RFC 7518 Section 4.7.1.2
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tag: Option<Bytes>,
+
+    /// RFC 7518 Section 4.8.1.1
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub p2s: Option<Bytes>,
+
+    /// RFC 7518 Section 4.8.1.2
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub p2c: Option<u32>,
+
+    /// RFC 7516 Section 4.1.13
+    ///
+    /// This must only appear in the JWE Protected Header (see
+    /// [`Protected::crit`]). It is parsed here so that it can be rejected.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub crit: Option<Vec<String>>,
+}
@@ -1,3 +1,6 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
 #![no_std]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![doc = include_str!("../README.md")]
@@ -15,3 +18,166 @@
     unused_lifetimes,
     unused_qualifications
 )]
+
+extern crate alloc;
+
+pub mod crypto;
+
+mod compact;
+mod head;
+
+pub use compact::CompactError;
+pub use head::{Protected, Unprotected};
+
+use alloc::{vec, vec::Vec};
+
+use jose_b64::serde::{Bytes, Json};
+use serde::{Deserialize, Serialize};
+
+/// A JSON Web Encryption representation
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+#[serde(untagged)]
+pub enum Jwe {
+    /// General Serialization
+    General(General),
+
+    /// Flattened Serialization
+    Flattened(Flattened),
+}
+
+impl From<General> for Jwe {
+    fn from(value: General) -> Self {
+        Jwe::General(value)
+    }
+}
+
+impl From<Flattened> for Jwe {
+    fn from(value: Flattened) -> Self {
+        Jwe::Flattened(value)
+    }
+}
+
+/// General Serialization
+///
+/// This is the usual JWE form, which allows the content to be encrypted to
+/// multiple recipients.
+///
+/// ```json
+/// {
+///     "protected":"<integrity-protected shared header contents>",
+///     "unprotected":<non-integrity-protected shared header contents>,
+///     "recipients":[
+///      {"header":<per-recipient unprotected header 1 contents>,
+///       "encrypted_key":"<encrypted key 1 contents>"},
+///      ...
+///      {"header":<per-recipient unprotected header N contents>,
+///       "encrypted_key":"<encrypted key N contents>"}],
+///     "aad":"<additional authenticated data contents>",
+///     "iv":"<initialization vector contents>",
+///     "ciphertext":"<ciphertext contents>",
+///     "tag":"<authentication tag contents>"
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct General {
+    /// The JWE Protected Header
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub protected: Option<Json<Protected>>,
+
+    /// The JWE Shared Unprotected Header
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub unprotected: Option<Unprotected>,
+
+    /// The recipients of the content encryption key.
+    pub recipients: Vec<Recipient>,
+
+    /// The JWE Additional Authenticated Data
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub aad: Option<Bytes>,
+
+    /// The JWE Initialization Vector
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iv: Option<Bytes>,
+
+    /// The JWE Ciphertext
+    pub ciphertext: Bytes,
+
+    /// The JWE Authentication Tag
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tag: Option<Bytes>,
+}
+
+impl From<Flattened> for General {
+    fn from(value: Flattened) -> Self {
+        Self {
+            protected: value.protected,
+            unprotected: value.unprotected,
+            recipients: vec![value.recipient],
+            aad: value.aad,
+            iv: value.iv,
+            ciphertext: value.ciphertext,
+            tag: value.tag,
+        }
+    }
+}
+
+/// Flattened Serialization
+///
+/// This is similar to the general serialization but is more compact, only
+/// supporting one recipient.
+///
+/// ```json
+/// {
+///     "protected":"<integrity-protected header contents>",
+///     "unprotected":<non-integrity-protected header contents>,
+///     "header":<more non-integrity-protected header contents>,
+///     "encrypted_key":"<encrypted key contents>",
+///     "aad":"<additional authenticated data contents>",
+///     "iv":"<initialization vector contents>",
+///     "ciphertext":"<ciphertext contents>",
+///     "tag":"<authentication tag contents>"
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Flattened {
+    /// The JWE Protected Header
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub protected: Option<Json<Protected>>,
+
+    /// The JWE Shared Unprotected Header
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub unprotected: Option<Unprotected>,
+
+    /// The recipient of the content encryption key.
+    #[serde(flatten)]
+    pub recipient: Recipient,
+
+    /// The JWE Additional Authenticated Data
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub aad: Option<Bytes>,
+
+    /// The JWE Initialization Vector
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iv: Option<Bytes>,
+
+    /// The JWE Ciphertext
+    pub ciphertext: Bytes,
+
+    /// The JWE Authentication Tag
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tag: Option<Bytes>,
+}
+
+/// A Recipient
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Recipient {
+    /// The JWE Per-Recipient Unprotected Header
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub header: Option<Unprotected>,
+
+    /// The JWE Encrypted Key
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub encrypted_key: Option<Bytes>,
+}
@@ -0,0 +1,546 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[cfg(test)]
+mod rfc7516 {
+    use jose_jwa::{Encryption, KeyManagement};
+    use jose_jwe::{CompactError, Flattened, General, Jwe};
+
+    const PROTECTED: &str = "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0";
+    const ENCRYPTED_KEY: &str = "6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ";
+    const IV: &str = "AxY8DCtDaGlsbGljb3RoZQ";
+    const CIPHERTEXT: &str = "KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY";
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.3.7
+    #[test]
+    fn a3() {
+        let compact =
+            format!("{PROTECTED}.{ENCRYPTED_KEY}.{IV}.{CIPHERTEXT}.U0m_YmjN04DJvceFICbCVQ");
+
+        let jwe: Flattened = compact.parse().unwrap();
+        let prot = jwe.protected.as_ref().unwrap();
+        assert_eq!(prot.oth.alg, Some(KeyManagement::A128Kw));
+        assert_eq!(prot.oth.enc, Some(Encryption::A128CbcHs256));
+        assert_eq!(prot.as_ref(), br#"{"alg":"A128KW","enc":"A128CBC-HS256"}"#);
+        assert_eq!(jwe.recipient.encrypted_key.as_ref().unwrap().len(), 40);
+        assert_eq!(jwe.iv.as_ref().unwrap().len(), 16);
+        assert_eq!(jwe.ciphertext.len(), 32);
+        assert_eq!(jwe.tag.as_ref().unwrap().len(), 16);
+        assert_eq!(jwe.to_string(), compact);
+
+        let general: General = compact.parse().unwrap();
+        assert_eq!(general.recipients.len(), 1);
+
+        // Compact JWEs have exactly five components.
+        assert!(format!("{compact}.").parse::<Flattened>().is_err());
+        assert!(format!("{PROTECTED}.{ENCRYPTED_KEY}.{IV}.{CIPHERTEXT}")
+            .parse::<Flattened>()
+            .is_err());
+    }
+
+    /// The compact form cannot represent unprotected headers or AAD.
+    #[test]
+    fn a3_unrepresentable() {
+        let compact =
+            format!("{PROTECTED}.{ENCRYPTED_KEY}.{IV}.{CIPHERTEXT}.U0m_YmjN04DJvceFICbCVQ");
+        let jwe: Flattened = compact.parse().unwrap();
+        assert_eq!(jwe.to_compact().unwrap(), compact);
+
+        let mut aad = jwe.clone();
+        aad.aad = Some(vec![1, 2, 3].into());
+        assert_eq!(aad.to_compact(), Err(CompactError::Aad));
+        assert_eq!(aad.to_string(), compact);
+
+        let mut unprotected = jwe.clone();
+        unprotected.unprotected = Some(Default::default());
+        assert_eq!(unprotected.to_compact(), Err(CompactError::Unprotected));
+
+        let mut header = jwe;
+        header.recipient.header = Some(Default::default());
+        assert_eq!(header.to_compact(), Err(CompactError::Unprotected));
+    }
+
+    /// Based on https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.5
+    #[test]
+    fn a5() {
+        let val = serde_json::json!({
+            "protected": "eyJlbmMiOiJBMTI4Q0JDLUhTMjU2In0",
+            "header": { "alg": "A128KW", "kid": "7" },
+            "encrypted_key": ENCRYPTED_KEY,
+            "iv": IV,
+            "ciphertext": CIPHERTEXT,
+            "tag": "Mz-VPPyU4RlcuYv1IwIvzw"
+        });
+
+        let jwe: Jwe = serde_json::from_value(val.clone()).unwrap();
+        let Jwe::Flattened(flattened) = &jwe else {
+            panic!("expected the flattened serialization");
+        };
+
+        let prot = flattened.protected.as_ref().unwrap();
+        assert_eq!(prot.oth.enc, Some(Encryption::A128CbcHs256));
+        let head = flattened.recipient.header.as_ref().unwrap();
+        assert_eq!(head.alg, Some(KeyManagement::A128Kw));
+        assert_eq!(head.kid.as_deref(), Some("7"));
+        assert_eq!(val, serde_json::to_value(&jwe).unwrap());
+    }
+
+    /// Based on https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.4.7
+    #[test]
+    fn a4() {
+        let val = serde_json::json!({
+            "protected": "eyJlbmMiOiJBMTI4Q0JDLUhTMjU2In0",
+            "unprotected": { "kid": "shared" },
+            "recipients": [
+                { "header": { "alg": "RSA1_5", "kid": "2011-04-29" }, "encrypted_key": "AAAA" },
+                { "header": { "alg": "A128KW", "kid": "7" }, "encrypted_key": ENCRYPTED_KEY }
+            ],
+            "aad": "AQID",
+            "iv": IV,
+            "ciphertext": CIPHERTEXT,
+            "tag": "Mz-VPPyU4RlcuYv1IwIvzw"
+        });
+
+        let jwe: Jwe = serde_json::from_value(val.clone()).unwrap();
+        let Jwe::General(general) = &jwe else {
+            panic!("expected the general serialization");
+        };
+
+        assert_eq!(general.recipients.len(), 2);
+        assert_eq!(&general.aad.as_ref().unwrap()[..], &[1, 2, 3]);
+        assert_eq!(val, serde_json::to_value(&jwe).unwrap());
+    }
+}
+
+#[cfg(all(test, feature = "crypto"))]
+mod crypto {
+    use jose_jwa::Encryption;
+    use jose_jwe::crypto::{aad, decrypt, encrypt, key_len};
+    use jose_jwe::Flattened;
+    use jose_jwk::crypto::Error;
+    use rand_core::OsRng;
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.3.2
+    const CEK: [u8; 32] = [
+        4, 211, 31, 197, 84, 157, 252, 254, 11, 100, 157, 250, 63, 170, 106, 206, 107, 124, 212,
+        45, 111, 107, 9, 219, 200, 177, 0, 240, 143, 156, 44, 207,
+    ];
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.3
+    #[test]
+    fn a128cbc_hs256() {
+        let jwe: Flattened = "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0.\
+            6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ.\
+            AxY8DCtDaGlsbGljb3RoZQ.\
+            KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY.\
+            U0m_YmjN04DJvceFICbCVQ"
+            .parse()
+            .unwrap();
+
+        assert_eq!(jwe.decrypt(&CEK).unwrap(), b"Live long and prosper.");
+
+        // Any modification of the authenticated data must be detected.
+        let mut tampered = jwe.clone();
+        tampered.tag.as_mut().unwrap()[0] ^= 1;
+        assert_eq!(tampered.decrypt(&CEK).unwrap_err(), Error::Invalid);
+
+        let mut tampered = jwe.clone();
+        tampered.ciphertext[0] ^= 1;
+        assert_eq!(tampered.decrypt(&CEK).unwrap_err(), Error::Invalid);
+
+        let mut tampered = jwe;
+        tampered.aad = Some(vec![1, 2, 3].into_boxed_slice().into());
+        assert_eq!(tampered.decrypt(&CEK).unwrap_err(), Error::Invalid);
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.1
+    #[test]
+    fn a256gcm() {
+        const CEK: [u8; 32] = [
+            177, 161, 244, 128, 84, 143, 225, 115, 63, 180, 3, 255, 107, 154, 212, 246, 138, 7,
+            110, 91, 112, 46, 34, 105, 47, 130, 203, 46, 122, 234, 64, 252,
+        ];
+
+        let jwe: Flattened = serde_json::from_value(serde_json::json!({
+            "protected": "eyJhbGciOiJSU0EtT0FFUCIsImVuYyI6IkEyNTZHQ00ifQ",
+            "iv": "48V1_ALb6US04U3b",
+            "ciphertext": "5eym8TW_c8SuK0ltJ3rpYIzOeDQz7TALvtu6UG9oMo4vpzs9tX_EFShS8iB7j6jiSdiwkIr3ajwQzaBtQD_A",
+            "tag": "XFBoMYUZodetZdvTiFvSkQ"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            jwe.decrypt(&CEK).unwrap(),
+            b"The true sign of intelligence is not knowledge but imagination."
+        );
+
+        let mut tampered = jwe;
+        tampered.tag.as_mut().unwrap()[15] ^= 1;
+        assert_eq!(tampered.decrypt(&CEK).unwrap_err(), Error::Invalid);
+    }
+
+    #[test]
+    fn roundtrip() {
+        use Encryption::*;
+
+        for enc in [
+            A128CbcHs256,
+            A192CbcHs384,
+            A256CbcHs512,
+            A128Gcm,
+            A192Gcm,
+            A256Gcm,
+        ] {
+            let cek = vec![7u8; key_len(enc)];
+            let aad = aad(None, Some(b"data"));
+
+            let e = encrypt(enc, &cek, &aad, b"hello", OsRng).unwrap();
+            let pt = decrypt(enc, &cek, &e.iv, &aad, &e.ciphertext, &e.tag).unwrap();
+            assert_eq!(pt, b"hello");
+
+            // The wrong key size or a different AAD is rejected.
+            assert!(encrypt(enc, &cek[1..], &aad, b"hello", OsRng).is_err());
+            let err = decrypt(enc, &cek, &e.iv, b"", &e.ciphertext, &e.tag).unwrap_err();
+            assert_eq!(err, Error::Invalid);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "crypto"))]
+mod oneshot {
+    use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+    use jose_jwa::{Encryption, KeyManagement, KeyManagement::*};
+    use jose_jwe::crypto::{open, seal, seal_all, unwrap};
+    use jose_jwe::{Jwe, Protected, Unprotected};
+    use jose_jwk::crypto::{Error, Key, Kind};
+    use jose_jwk::Jwk;
+    use rand_core::OsRng;
+
+    const PLAINTEXT: &[u8] = b"Live long and prosper.";
+
+    const ALGS: &[KeyManagement] = &[
+        Rsa1_5,
+        RsaOaep,
+        RsaOaep256,
+        A128Kw,
+        A192Kw,
+        A256Kw,
+        Direct,
+        EcdhEs,
+        EcdhEsA128Kw,
+        EcdhEsA192Kw,
+        EcdhEsA256Kw,
+        A128GcmKw,
+        A192GcmKw,
+        A256GcmKw,
+        Pbes2Hs256A128Kw,
+        Pbes2Hs384A192Kw,
+        Pbes2Hs512A256Kw,
+    ];
+
+    const ENCS: &[Encryption] = &[
+        Encryption::A128CbcHs256,
+        Encryption::A192CbcHs384,
+        Encryption::A256CbcHs512,
+        Encryption::A128Gcm,
+        Encryption::A192Gcm,
+        Encryption::A256Gcm,
+    ];
+
+    fn key(jwk: serde_json::Value) -> Key {
+        let jwk: Jwk = serde_json::from_value(jwk).unwrap();
+        Key::try_from(&jwk.key).unwrap()
+    }
+
+    fn oct(len: usize) -> Key {
+        Key::Oct(vec![9u8; len].into_boxed_slice().into())
+    }
+
+    /// Bob's key from https://datatracker.ietf.org/doc/html/rfc7518#appendix-C
+    fn bob() -> Key {
+        key(serde_json::json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": "weNJy2HscCSM6AEDTDg04biOvhFhyyWvOHQfeF_PxMQ",
+            "y": "e8lnCO-AlStT-NJVX-crhB7QRYhiix03illJOVAOyck",
+            "d": "VEmDZpDXXK8p8N0Cndsxs924q6nS1RXFASRl6BfUqdw"
+        }))
+    }
+
+    /// The RSA key from https://datatracker.ietf.org/doc/html/rfc7517#appendix-A.2
+    fn rsa() -> Key {
+        key(serde_json::json!({
+            "kty": "RSA",
+            "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+            "e": "AQAB",
+            "d": "X4cTteJY_gn4FYPsXB8rdXix5vwsg1FLN5E3EaG6RJoVH-HLLKD9M7dx5oo7GURknchnrRweUkC7hT5fJLM0WbFAKNLWY2vv7B6NqXSzUvxT0_YSfqijwp3RTzlBaCxWp4doFk5N2o8Gy_nHNKroADIkJ46pRUohsXywbReAdYaMwFs9tv8d_cPVY3i07a3t8MN6TNwm0dSawm9v47UiCl3Sk5ZiG7xojPLu4sbg1U2jx4IBTNBznbJSzFHK66jT8bgkuqsk0GjskDJk19Z4qwjwbsnn4j2WBii3RL-Us2lGVkY8fkFzme1z0HbIkfz0Y6mqnOYtqc0X4jfcKoAC8Q",
+            "p": "83i-7IvMGXoMXCskv73TKr8637FiO7Z27zv8oj6pbWUQyLPQBQxtPVnwD20R-60eTDmD2ujnMt5PoqMrm8RfmNhVWDtjjMmCMjOpSXicFHj7XOuVIYQyqVWlWEh6dN36GVZYk93N8Bc9vY41xy8B9RzzOGVQzXvNEvn7O0nVbfs",
+            "q": "3dfOR9cuYq-0S-mkFLzgItgMEfFzB2q3hWehMuG0oCuqnb3vobLyumqjVZQO1dIrdwgTnCdpYzBcOfW5r370AFXjiWft_NGEiovonizhKpo9VVS78TzFgxkIdrecRezsZ-1kYd_s1qDbxtkDEgfAITAG9LUnADun4vIcb6yelxk",
+            "dp": "G4sPXkc6Ya9y8oJW9_ILj4xuppu0lzi_H7VTkS8xj5SdX3coE0oimYwxIi2emTAue0UOa5dpgFGyBJ4c8tQ2VF402XRugKDTP8akYhFo5tAA77Qe_NmtuYZc3C3m3I24G2GvR5sSDxUyAN2zq8Lfn9EUms6rY3Ob8YeiKkTiBj0",
+            "dq": "s9lAH9fggBsoFR8Oac2R_E2gw282rT2kGOAhvIllETE1efrA6huUUvMfBcMpn8lqeW6vzznYY5SSQF7pMdC_agI3nG8Ibp1BUb0JUiraRNqUfLhcQb_d9GF4Dh7e74WbRsobRonujTYN1xCaP6TO61jvWrX-L18txXw494Q_cgk",
+            "qi": "GyM_p6JrXySiz1toFgKbWV-JdI3jQ4ypu9rbMWx3rQJBfmt0FoYzgUIZEVFEcOqwemRN81zoDAaa-Bk0KWNGDjJHZDdDmFhW3AN7lI-puxk_mHZGJ11rxyR8O55XLSe3SPmRfKwZI6yU24ZxvQKFYItdldUKGzO6Ia6zTKhAVRU",
+        }))
+    }
+
+    fn public(key: Key) -> Key {
+        match key {
+            Key::Rsa(Kind::Secret(key)) => Key::from(key.to_public_key()),
+            Key::P256(Kind::Secret(key)) => Key::from(key.public_key()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn protected(alg: Option<KeyManagement>, enc: Encryption) -> Protected {
+        Protected {
+            oth: Unprotected {
+                alg,
+                enc: Some(enc),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn header(alg: KeyManagement) -> Option<Unprotected> {
+        Some(Unprotected {
+            alg: Some(alg),
+            ..Default::default()
+        })
+    }
+
+    fn roundtrip(sender: &Key, recipient: &Key, alg: KeyManagement, enc: Encryption) -> Jwe {
+        let prot = protected(Some(alg), enc);
+        let jwe = seal(sender, prot, None, PLAINTEXT, OsRng).unwrap();
+        assert_eq!(open(recipient, &jwe, ALGS, ENCS, OsRng).unwrap(), PLAINTEXT);
+
+        // Everything is in the protected header, so compact serialization works.
+        let Jwe::Flattened(flattened) = &jwe else {
+            panic!("expected the flattened serialization");
+        };
+        let compact = flattened.to_compact().unwrap().parse().unwrap();
+        assert_eq!(
+            open(recipient, &Jwe::Flattened(compact), ALGS, ENCS, OsRng).unwrap(),
+            PLAINTEXT
+        );
+
+        jwe
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7516#appendix-A.3
+    #[test]
+    fn a128kw() {
+        let key = key(serde_json::json!({"kty":"oct","k":"GawgguFyGrWKav7AX4VKUg"}));
+        let jwe: Jwe = "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0.\
+            6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ.\
+            AxY8DCtDaGlsbGljb3RoZQ.\
+            KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY.\
+            U0m_YmjN04DJvceFICbCVQ"
+            .parse()
+            .unwrap();
+
+        assert_eq!(open(&key, &jwe, ALGS, ENCS, OsRng).unwrap(), PLAINTEXT);
+        assert_eq!(
+            open(&oct(16), &jwe, ALGS, ENCS, OsRng).unwrap_err(),
+            Error::Invalid
+        );
+        assert_eq!(
+            open(&oct(32), &jwe, ALGS, ENCS, OsRng).unwrap_err(),
+            Error::AlgMismatch
+        );
+
+        // Algorithms outside of the allow-lists are rejected.
+        let err = open(&key, &jwe, &[A256Kw], ENCS, OsRng).unwrap_err();
+        assert_eq!(err, Error::Forbidden);
+        let err = open(&key, &jwe, ALGS, &[Encryption::A128Gcm], OsRng).unwrap_err();
+        assert_eq!(err, Error::Forbidden);
+
+        for (alg, len) in [(A128Kw, 16), (A192Kw, 24), (A256Kw, 32)] {
+            roundtrip(&oct(len), &oct(len), alg, Encryption::A256Gcm);
+        }
+    }
+
+    /// RFC 7516 Section 4.1.13: no extensions are understood.
+    #[test]
+    fn crit() {
+        let key = oct(16);
+        let jwe = seal(
+            &key,
+            protected(Some(A128Kw), Encryption::A128Gcm),
+            None,
+            PLAINTEXT,
+            OsRng,
+        )
+        .unwrap();
+        let Jwe::Flattened(flattened) = jwe else {
+            unreachable!()
+        };
+
+        let mut val = serde_json::to_value(&flattened).unwrap();
+        val["protected"] = serde_json::json!(Base64UrlUnpadded::encode_string(
+            br#"{"alg":"A128KW","enc":"A128GCM","crit":["exp"],"exp":1}"#
+        ));
+        let jwe: Jwe = serde_json::from_value(val).unwrap();
+        let err = open(&key, &jwe, ALGS, ENCS, OsRng).unwrap_err();
+        assert_eq!(err, Error::Unsupported);
+
+        // `crit` must not appear in an unprotected header.
+        for field in ["unprotected", "header"] {
+            let mut val = serde_json::to_value(&flattened).unwrap();
+            val[field] = serde_json::json!({ "crit": ["exp"], "exp": 1 });
+            let jwe: Jwe = serde_json::from_value(val).unwrap();
+            let err = open(&key, &jwe, ALGS, ENCS, OsRng).unwrap_err();
+            assert_eq!(err, Error::Unsupported);
+        }
+
+        let jwe = Jwe::Flattened(flattened);
+        assert_eq!(open(&key, &jwe, ALGS, ENCS, OsRng).unwrap(), PLAINTEXT);
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7518#appendix-C
+    #[test]
+    fn ecdh_es() {
+        let head: Unprotected = serde_json::from_value(serde_json::json!({
+            "alg": "ECDH-ES",
+            "enc": "A128GCM",
+            "apu": "QWxpY2U",
+            "apv": "Qm9i",
+            "epk": {
+                "kty": "EC",
+                "crv": "P-256",
+                "x": "gI0GAILBdu7T53akrFmMyGcsF3n5dO7MmwNBHKW5SV0",
+                "y": "SLW_xSffzlPWrHEVI30DHM_4egVwt3NQqeUD7nMFpps"
+            }
+        }))
+        .unwrap();
+
+        let cek = unwrap(&bob(), EcdhEs, Encryption::A128Gcm, &head, &[], OsRng).unwrap();
+        assert_eq!(
+            cek.as_slice(),
+            [86, 170, 141, 234, 248, 35, 109, 32, 92, 34, 40, 205, 113, 167, 16, 26]
+        );
+
+        // Only the private key can complete the key agreement.
+        let err = unwrap(
+            &public(bob()),
+            EcdhEs,
+            Encryption::A128Gcm,
+            &head,
+            &[],
+            OsRng,
+        );
+        assert_eq!(err.unwrap_err(), Error::NotPrivate);
+
+        for alg in [EcdhEs, EcdhEsA128Kw, EcdhEsA192Kw, EcdhEsA256Kw] {
+            let jwe = roundtrip(&public(bob()), &bob(), alg, Encryption::A128CbcHs256);
+            let Jwe::Flattened(flattened) = jwe else {
+                unreachable!()
+            };
+
+            let prot = flattened.protected.unwrap();
+            assert!(prot.oth.epk.is_some());
+            assert_eq!(flattened.recipient.encrypted_key.is_none(), alg == EcdhEs);
+        }
+    }
+
+    #[test]
+    fn rsa_oaep() {
+        for alg in [Rsa1_5, RsaOaep, RsaOaep256] {
+            roundtrip(&public(rsa()), &rsa(), alg, Encryption::A128Gcm);
+        }
+
+        // A corrupt RSA1_5 key is only detected during content decryption.
+        let jwe = seal(
+            &rsa(),
+            protected(Some(Rsa1_5), Encryption::A128Gcm),
+            None,
+            PLAINTEXT,
+            OsRng,
+        );
+        let Jwe::Flattened(mut flattened) = jwe.unwrap() else {
+            unreachable!()
+        };
+        flattened.recipient.encrypted_key.as_mut().unwrap()[0] ^= 1;
+        let err = open(&rsa(), &flattened.into(), ALGS, ENCS, OsRng).unwrap_err();
+        assert_eq!(err, Error::Invalid);
+    }
+
+    #[test]
+    fn gcmkw() {
+        for (alg, len) in [(A128GcmKw, 16), (A192GcmKw, 24), (A256GcmKw, 32)] {
+            let Jwe::Flattened(jwe) = roundtrip(&oct(len), &oct(len), alg, Encryption::A256Gcm)
+            else {
+                unreachable!()
+            };
+
+            let prot = jwe.protected.unwrap();
+            assert_eq!(prot.oth.iv.as_ref().unwrap().len(), 12);
+            assert_eq!(prot.oth.tag.as_ref().unwrap().len(), 16);
+        }
+    }
+
+    #[test]
+    fn pbes2() {
+        let password = Key::Oct(b"Thus from my lips".to_vec().into_boxed_slice().into());
+
+        for alg in [Pbes2Hs256A128Kw, Pbes2Hs384A192Kw, Pbes2Hs512A256Kw] {
+            let mut prot = protected(Some(alg), Encryption::A128CbcHs256);
+            prot.oth.p2c = Some(4096);
+
+            let jwe = seal(&password, prot, None, PLAINTEXT, OsRng).unwrap();
+            assert_eq!(open(&password, &jwe, ALGS, ENCS, OsRng).unwrap(), PLAINTEXT);
+
+            let Jwe::Flattened(jwe) = jwe else {
+                unreachable!()
+            };
+            let prot = jwe.protected.as_ref().unwrap();
+            assert_eq!(prot.oth.p2c, Some(4096));
+            assert_eq!(prot.oth.p2s.as_ref().unwrap().len(), 16);
+        }
+
+        // Too few iterations are rejected.
+        let mut prot = protected(Some(Pbes2Hs256A128Kw), Encryption::A128Gcm);
+        prot.oth.p2c = Some(999);
+        let err = seal(&password, prot, None, PLAINTEXT, OsRng).unwrap_err();
+        assert_eq!(err, Error::Invalid);
+    }
+
+    #[test]
+    fn direct() {
+        roundtrip(&oct(32), &oct(32), Direct, Encryption::A256Gcm);
+        roundtrip(&oct(64), &oct(64), Direct, Encryption::A256CbcHs512);
+
+        // The key must have the size of the content encryption key.
+        let prot = protected(Some(Direct), Encryption::A128Gcm);
+        let err = seal(&oct(32), prot, None, PLAINTEXT, OsRng).unwrap_err();
+        assert_eq!(err, Error::Invalid);
+    }
+
+    #[test]
+    fn general() {
+        let (kw, rsa) = (oct(16), rsa());
+        let keys = [(&kw, header(A128Kw)), (&rsa, header(RsaOaep256))];
+        let prot = protected(None, Encryption::A256Gcm);
+        let jwe = seal_all(keys, prot, PLAINTEXT, OsRng).unwrap();
+
+        let Jwe::General(general) = &jwe else {
+            panic!("expected the general serialization");
+        };
+        assert_eq!(general.recipients.len(), 2);
+
+        assert_eq!(open(&kw, &jwe, ALGS, ENCS, OsRng).unwrap(), PLAINTEXT);
+        assert_eq!(open(&rsa, &jwe, ALGS, ENCS, OsRng).unwrap(), PLAINTEXT);
+        assert!(open(&oct(24), &jwe, ALGS, ENCS, OsRng).is_err());
+
+        // Direct modes cannot be used with multiple recipients.
+        let keys = [(&kw, header(A128Kw)), (&bob(), header(EcdhEs))];
+        let prot = protected(None, Encryption::A256Gcm);
+        let err = seal_all(keys, prot, PLAINTEXT, OsRng).unwrap_err();
+        assert_eq!(err, Error::Invalid);
+
+        // Header parameters must not be duplicated.
+        let keys = [(&kw, header(A128Kw))];
+        let prot = protected(Some(A128Kw), Encryption::A256Gcm);
+        let err = seal_all(keys, prot, PLAINTEXT, OsRng).unwrap_err();
+        assert_eq!(err, Error::Invalid);
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(feature = "ed25519")]
+
+use ed25519_dalek::{SigningKey, VerifyingKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+
+use jose_jwa::{Algorithm, Algorithm::Signing, Signing::*};
+
+use super::Error;
+use super::KeyInfo;
+use crate::{Okp, OkpCurves};
+
+impl KeyInfo for VerifyingKey {
+    fn strength(&self) -> usize {
+        16
+    }
+
+    fn is_supported(&self, algo: &Algorithm) -> bool {
+        matches!(algo, Signing(EdDsa))
+    }
+}
+
+impl KeyInfo for SigningKey {
+    fn strength(&self) -> usize {
+        16
+    }
+
+    fn is_supported(&self, algo: &Algorithm) -> bool {
+        matches!(algo, Signing(EdDsa))
+    }
+}
+
+impl From<&VerifyingKey> for Okp {
+    fn from(pk: &VerifyingKey) -> Self {
+        Self {
+            crv: OkpCurves::Ed25519,
+            x: pk.to_bytes().to_vec().into(),
+            d: None,
+        }
+    }
+}
+
+impl From<VerifyingKey> for Okp {
+    fn from(pk: VerifyingKey) -> Self {
+        (&pk).into()
+    }
+}
+
+impl TryFrom<&Okp> for VerifyingKey {
+    type Error = Error;
+
+    fn try_from(value: &Okp) -> Result<Self, Self::Error> {
+        if value.crv != OkpCurves::Ed25519 {
+            return Err(Error::AlgMismatch);
+        }
+
+        let x = <&[u8; PUBLIC_KEY_LENGTH]>::try_from(&value.x[..]).map_err(|_| Error::Invalid)?;
+        Self::from_bytes(x).map_err(|_| Error::Invalid)
+    }
+}
+
+impl TryFrom<Okp> for VerifyingKey {
+    type Error = Error;
+
+    fn try_from(value: Okp) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
+
+impl From<&SigningKey> for Okp {
+    fn from(sk: &SigningKey) -> Self {
+        let mut key: Self = sk.verifying_key().into();
+        key.d = Some(sk.to_bytes().to_vec().into());
+        key
+    }
+}
+
+impl From<SigningKey> for Okp {
+    fn from(sk: SigningKey) -> Self {
+        (&sk).into()
+    }
+}
+
+impl TryFrom<&Okp> for SigningKey {
+    type Error = Error;
+
+    fn try_from(value: &Okp) -> Result<Self, Self::Error> {
+        if value.crv != OkpCurves::Ed25519 {
+            return Err(Error::AlgMismatch);
+        }
+
+        let d = value.d.as_ref().ok_or(Error::NotPrivate)?;
+        let d = <&[u8; SECRET_KEY_LENGTH]>::try_from(&d[..]).map_err(|_| Error::Invalid)?;
+        let key = Self::from_bytes(d);
+
+        // RFC 8037 Section 2 requires `x` to be the matching public key.
+        if key.verifying_key().as_bytes()[..] != value.x[..] {
+            return Err(Error::Invalid);
+        }
+
+        Ok(key)
+    }
+}
+
+impl TryFrom<Okp> for SigningKey {
+    type Error = Error;
+
+    fn try_from(value: Okp) -> Result<Self, Self::Error> {
+        (&value).try_into()
+    }
+}
@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::vec;
+
+use jose_jwa::Signing;
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+use super::{Error, Key};
+use crate::{Jwk, OkpCurves, Operations, Parameters};
+
+/// Generation of fresh signing keys.
+///
+/// The generated JWK contains the private key along with the `alg`, `use`
+/// and `key_ops` parameters for the algorithm. The fields configure the
+/// choices which the algorithm leaves open.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Generator {
+    /// The size of RSA keys, in bits.
+    ///
+    /// When unset, the smallest size supported (see
+    /// [`KeyInfo`](super::KeyInfo)) for the algorithm is used: 2048 bits for
+    /// `RS256` and `PS256`, 3072 bits for `RS384` and `PS384` and 4096 bits
+    /// for `RS512` and `PS512`. Smaller sizes are rejected.
+    pub rsa_bits: Option<usize>,
+
+    /// The curve of `EdDSA` keys.
+    pub okp_curve: OkpCurves,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            rsa_bits: None,
+            okp_curve: OkpCurves::Ed25519,
+        }
+    }
+}
+
+impl Generator {
+    /// Generates a key for the signing algorithm.
+    ///
+    /// HMAC keys are as long as the output of the hash function, as required
+    /// by RFC 7518 Section 3.2. Unsecured JWS (`none`) have no key, so
+    /// [`Signing::Null`] is rejected as [`Error::Unsupported`], as are
+    /// algorithms whose backend is not enabled.
+    #[cfg_attr(
+        not(all(
+            feature = "p256",
+            feature = "p384",
+            feature = "p521",
+            feature = "k256",
+            feature = "rsa",
+            feature = "ed25519",
+            feature = "ed448"
+        )),
+        allow(unused_variables)
+    )]
+    pub fn generate(&self, alg: Signing, rng: &mut impl CryptoRngCore) -> Result<Jwk, Error> {
+        let key: Key = match alg {
+            Signing::Hs256 => oct(32, rng),
+            Signing::Hs384 => oct(48, rng),
+            Signing::Hs512 => oct(64, rng),
+
+            #[cfg(feature = "p256")]
+            Signing::Es256 => p256::SecretKey::random(rng).into(),
+
+            #[cfg(feature = "p384")]
+            Signing::Es384 => p384::SecretKey::random(rng).into(),
+
+            #[cfg(feature = "p521")]
+            Signing::Es512 => p521::SecretKey::random(rng).into(),
+
+            #[cfg(feature = "k256")]
+            Signing::Es256K => k256::SecretKey::random(rng).into(),
+
+            #[cfg(feature = "rsa")]
+            Signing::Rs256 | Signing::Ps256 => self.rsa(2048, rng)?,
+
+            #[cfg(feature = "rsa")]
+            Signing::Rs384 | Signing::Ps384 => self.rsa(3072, rng)?,
+
+            #[cfg(feature = "rsa")]
+            Signing::Rs512 | Signing::Ps512 => self.rsa(4096, rng)?,
+
+            Signing::EdDsa => match self.okp_curve {
+                #[cfg(feature = "ed25519")]
+                OkpCurves::Ed25519 => {
+                    let mut seed = Zeroizing::new([0u8; 32]);
+                    rng.fill_bytes(&mut seed[..]);
+                    ed25519_dalek::SigningKey::from_bytes(&seed).into()
+                }
+
+                #[cfg(feature = "ed448")]
+                OkpCurves::Ed448 => {
+                    let mut seed = Zeroizing::new([0u8; 57]);
+                    rng.fill_bytes(&mut seed[..]);
+                    ed448_goldilocks_plus::SigningKey::try_from(&seed[..])
+                        .map_err(|_| Error::Invalid)?
+                        .into()
+                }
+
+                OkpCurves::X25519 | OkpCurves::X448 => return Err(Error::AlgMismatch),
+
+                #[allow(unreachable_patterns)]
+                _ => return Err(Error::Unsupported),
+            },
+
+            _ => return Err(Error::Unsupported),
+        };
+
+        let prm = Parameters {
+            ops: Some([Operations::Sign, Operations::Verify].into()),
+            ..alg.into()
+        };
+
+        Ok(Jwk {
+            key: (&key).into(),
+            prm,
+        })
+    }
+
+    #[cfg(feature = "rsa")]
+    fn rsa(&self, min: usize, rng: &mut impl CryptoRngCore) -> Result<Key, Error> {
+        let bits = self.rsa_bits.unwrap_or(min);
+        if bits < min {
+            return Err(Error::Invalid);
+        }
+
+        let key = rsa::RsaPrivateKey::new(rng, bits).map_err(|_| Error::Invalid)?;
+        Ok(key.into())
+    }
+}
+
+fn oct(len: usize, rng: &mut impl CryptoRngCore) -> Key {
+    let mut k = Zeroizing::new(vec![0u8; len].into_boxed_slice());
+    rng.fill_bytes(&mut k);
+    Key::Oct(k)
+}
+
+/// Generates a key for the signing algorithm with the default [`Generator`].
+pub fn generate(alg: Signing, rng: &mut impl CryptoRngCore) -> Result<Jwk, Error> {
+    Generator::default().generate(alg, rng)
+}
@@ -19,6 +19,7 @@ use super::KeyInfo;
 /// behavior of a JWK, this structure allows us to represent the different
 /// kinds of JWKs at runtime using a single object.
 #[allow(clippy::large_enum_variant)]
+#[non_exhaustive]
 pub enum Key {
     /// A symmetric key.
     Oct(Zeroizing<Box<[u8]>>),
@@ -42,6 +43,14 @@ pub enum Key {
     /// A Secp256k1 key.
     #[cfg(feature = "k256")]
     P256K(super::Kind<k256::PublicKey, k256::SecretKey>),
+
+    /// An Ed25519 key.
+    #[cfg(feature = "ed25519")]
+    Ed25519(super::Kind<ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey>),
+
+    /// An Ed448 key.
+    #[cfg(feature = "ed448")]
+    Ed448(super::Kind<ed448_goldilocks_plus::VerifyingKey, ed448_goldilocks_plus::SigningKey>),
 }
 
 impl KeyInfo for Key {
@@ -63,6 +72,12 @@ impl KeyInfo for Key {
 
             #[cfg(feature = "k256")]
             Self::P256K(k) => k.strength(),
+
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(k) => k.strength(),
+
+            #[cfg(feature = "ed448")]
+            Self::Ed448(k) => k.strength(),
         }
     }
 
@@ -84,6 +99,12 @@ impl KeyInfo for Key {
 
             #[cfg(feature = "k256")]
             Key::P256K(k) => k.is_supported(algo),
+
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(k) => k.is_supported(algo),
+
+            #[cfg(feature = "ed448")]
+            Self::Ed448(k) => k.is_supported(algo),
         }
     }
 }
@@ -178,6 +199,73 @@ impl From<p521::SecretKey> for Key {
     }
 }
 
+#[cfg(feature = "k256")]
+impl From<super::Kind<k256::PublicKey, k256::SecretKey>> for Key {
+    fn from(value: super::Kind<k256::PublicKey, k256::SecretKey>) -> Self {
+        Self::P256K(value)
+    }
+}
+
+#[cfg(feature = "k256")]
+impl From<k256::PublicKey> for Key {
+    fn from(value: k256::PublicKey) -> Self {
+        Self::P256K(super::Kind::Public(value))
+    }
+}
+
+#[cfg(feature = "k256")]
+impl From<k256::SecretKey> for Key {
+    fn from(value: k256::SecretKey) -> Self {
+        Self::P256K(super::Kind::Secret(value))
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl From<super::Kind<ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey>> for Key {
+    fn from(value: super::Kind<ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey>) -> Self {
+        Self::Ed25519(value)
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl From<ed25519_dalek::VerifyingKey> for Key {
+    fn from(value: ed25519_dalek::VerifyingKey) -> Self {
+        Self::Ed25519(super::Kind::Public(value))
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl From<ed25519_dalek::SigningKey> for Key {
+    fn from(value: ed25519_dalek::SigningKey) -> Self {
+        Self::Ed25519(super::Kind::Secret(value))
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl From<super::Kind<ed448_goldilocks_plus::VerifyingKey, ed448_goldilocks_plus::SigningKey>>
+    for Key
+{
+    fn from(
+        value: super::Kind<ed448_goldilocks_plus::VerifyingKey, ed448_goldilocks_plus::SigningKey>,
+    ) -> Self {
+        Self::Ed448(value)
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl From<ed448_goldilocks_plus::VerifyingKey> for Key {
+    fn from(value: ed448_goldilocks_plus::VerifyingKey) -> Self {
+        Self::Ed448(super::Kind::Public(value))
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl From<ed448_goldilocks_plus::SigningKey> for Key {
+    fn from(value: ed448_goldilocks_plus::SigningKey) -> Self {
+        Self::Ed448(super::Kind::Secret(value))
+    }
+}
+
 impl From<&crate::Oct> for Key {
     fn from(value: &crate::Oct) -> Self {
         Self::Oct(value.k.to_vec().into_boxed_slice().into())
@@ -193,7 +281,7 @@ impl TryFrom<&crate::Rsa> for Key {
     }
 }
 
-#[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+#[cfg(any(feature = "p256", feature = "p384", feature = "p521", feature = "k256"))]
 impl TryFrom<&crate::Ec> for Key {
     type Error = super::Error;
 
@@ -208,6 +296,28 @@ impl TryFrom<&crate::Ec> for Key {
             #[cfg(feature = "p521")]
             crate::EcCurves::P521 => Ok(Self::P521(value.try_into()?)),
 
+            #[cfg(feature = "k256")]
+            crate::EcCurves::P256K => Ok(Self::P256K(value.try_into()?)),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(super::Error::Unsupported),
+        }
+    }
+}
+
+#[cfg(any(feature = "ed25519", feature = "ed448"))]
+impl TryFrom<&crate::Okp> for Key {
+    type Error = super::Error;
+
+    fn try_from(value: &crate::Okp) -> Result<Self, Self::Error> {
+        match value.crv {
+            #[cfg(feature = "ed25519")]
+            crate::OkpCurves::Ed25519 => Ok(Self::Ed25519(value.try_into()?)),
+
+            #[cfg(feature = "ed448")]
+            crate::OkpCurves::Ed448 => Ok(Self::Ed448(value.try_into()?)),
+
+            #[allow(unreachable_patterns)]
             _ => Err(super::Error::Unsupported),
         }
     }
@@ -223,9 +333,13 @@ impl TryFrom<&crate::Key> for Key {
             #[cfg(feature = "rsa")]
             crate::Key::Rsa(rsa) => rsa.try_into(),
 
-            #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+            #[cfg(any(feature = "p256", feature = "p384", feature = "p521", feature = "k256"))]
             crate::Key::Ec(ec) => ec.try_into(),
 
+            #[cfg(any(feature = "ed25519", feature = "ed448"))]
+            crate::Key::Okp(okp) => okp.try_into(),
+
+            #[allow(unreachable_patterns)]
             _ => Err(super::Error::Unsupported),
         }
     }
@@ -267,6 +381,18 @@ impl From<&Key> for crate::Key {
                 super::Kind::Public(public) => Self::Ec(public.into()),
                 super::Kind::Secret(secret) => Self::Ec(secret.into()),
             },
+
+            #[cfg(feature = "ed25519")]
+            Key::Ed25519(kind) => match kind {
+                super::Kind::Public(public) => Self::Okp(public.into()),
+                super::Kind::Secret(secret) => Self::Okp(secret.into()),
+            },
+
+            #[cfg(feature = "ed448")]
+            Key::Ed448(kind) => match kind {
+                super::Kind::Public(public) => Self::Okp(public.into()),
+                super::Kind::Secret(secret) => Self::Okp(secret.into()),
+            },
         }
     }
 }
@@ -144,3 +144,55 @@ impl TryFrom<&crate::Ec> for Kind<k256::PublicKey, k256::SecretKey> {
         }
     }
 }
+
+#[cfg(feature = "ed25519")]
+impl From<&Kind<ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey>> for crate::Okp {
+    fn from(value: &Kind<ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey>) -> Self {
+        match value {
+            Kind::Public(key) => key.into(),
+            Kind::Secret(key) => key.into(),
+        }
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl TryFrom<&crate::Okp> for Kind<ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey> {
+    type Error = super::Error;
+
+    fn try_from(value: &crate::Okp) -> Result<Self, Self::Error> {
+        if value.d.is_none() {
+            Ok(Kind::Public(value.try_into()?))
+        } else {
+            Ok(Kind::Secret(value.try_into()?))
+        }
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl From<&Kind<ed448_goldilocks_plus::VerifyingKey, ed448_goldilocks_plus::SigningKey>>
+    for crate::Okp
+{
+    fn from(
+        value: &Kind<ed448_goldilocks_plus::VerifyingKey, ed448_goldilocks_plus::SigningKey>,
+    ) -> Self {
+        match value {
+            Kind::Public(key) => key.into(),
+            Kind::Secret(key) => key.into(),
+        }
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl TryFrom<&crate::Okp>
+    for Kind<ed448_goldilocks_plus::VerifyingKey, ed448_goldilocks_plus::SigningKey>
+{
+    type Error = super::Error;
+
+    fn try_from(value: &crate::Okp) -> Result<Self, Self::Error> {
+        if value.d.is_none() {
+            Ok(Kind::Public(value.try_into()?))
+        } else {
+            Ok(Kind::Secret(value.try_into()?))
+        }
+    }
+}
@@ -3,6 +3,9 @@
 
 //! Cryptographic primitives for JWK
 
+mod ed25519;
+mod ed448;
+mod generate;
 mod k256;
 mod key;
 mod keyinfo;
@@ -11,15 +14,19 @@ mod p256;
 mod p384;
 mod p521;
 mod rsa;
+mod usage;
 
+pub use generate::{generate, Generator};
 pub use key::Key;
 pub use keyinfo::KeyInfo;
 pub use kind::Kind;
+pub use usage::Restricted;
 
 use core::convert::Infallible;
 
 /// An error related to key material.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
     /// The inputs are invalid.
     #[default]
@@ -33,6 +40,10 @@ pub enum Error {
 
     /// The specified criteria are unsupported.
     Unsupported,
+
+    /// The key's `use` or `key_ops`, or the algorithms allowed by the
+    /// caller, do not permit the operation.
+    Forbidden,
 }
 
 impl From<Infallible> for Error {
@@ -6,7 +6,9 @@
 use p521::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
 use p521::{EncodedPoint, FieldBytes, PublicKey, SecretKey};
 
-use jose_jwa::{Algorithm, Algorithm::Signing, Signing::*};
+use jose_jwa::{
+    Algorithm, Algorithm::KeyManagement, Algorithm::Signing, KeyManagement::*, Signing::*,
+};
 
 use super::Error;
 use super::KeyInfo;
@@ -18,7 +20,10 @@ impl KeyInfo for PublicKey {
     }
 
     fn is_supported(&self, algo: &Algorithm) -> bool {
-        matches!(algo, Signing(Es512))
+        matches!(
+            algo,
+            Signing(Es512) | KeyManagement(EcdhEs | EcdhEsA128Kw | EcdhEsA192Kw | EcdhEsA256Kw)
+        )
     }
 }
 
@@ -28,7 +33,10 @@ impl KeyInfo for SecretKey {
     }
 
     fn is_supported(&self, algo: &Algorithm) -> bool {
-        matches!(algo, Signing(Es512))
+        matches!(
+            algo,
+            Signing(Es512) | KeyManagement(EcdhEs | EcdhEsA128Kw | EcdhEsA192Kw | EcdhEsA256Kw)
+        )
     }
 }
 
@@ -8,7 +8,9 @@ use rsa::{
     BigUint, RsaPrivateKey, RsaPublicKey,
 };
 
-use jose_jwa::{Algorithm, Algorithm::Signing, Signing::*};
+use jose_jwa::{
+    Algorithm, Algorithm::KeyManagement, Algorithm::Signing, KeyManagement::*, Signing::*,
+};
 
 use super::Error;
 use super::KeyInfo;
@@ -37,6 +39,9 @@ impl KeyInfo for RsaPublicKey {
             Signing(Ps256) => true,
             Signing(Ps384) => true,
             Signing(Ps512) => true,
+            KeyManagement(Rsa1_5) => true,
+            KeyManagement(RsaOaep) => true,
+            KeyManagement(RsaOaep256) => true,
             _ => false,
         }
     }
@@ -56,6 +61,9 @@ impl KeyInfo for RsaPrivateKey {
             (Signing(Ps256), 16..) => true,
             (Signing(Ps384), 24..) => true,
             (Signing(Ps512), 32..) => true,
+            (KeyManagement(Rsa1_5), 16..) => true,
+            (KeyManagement(RsaOaep), 16..) => true,
+            (KeyManagement(RsaOaep256), 16..) => true,
             _ => false,
         }
     }
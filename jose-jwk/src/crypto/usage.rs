@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::collections::BTreeSet;
+
+use jose_jwa::KeyManagement;
+
+use super::{Error, Key};
+use crate::{Class, Jwk, Operations, Parameters};
+
+/// A key restricted to the operations permitted by its JWK parameters.
+///
+/// The permitted operations are those of the `key_ops` parameter, if
+/// present, or else those of the `use` parameter. A key with neither is
+/// unrestricted. RFC 7517 Section 4.3 requires the two parameters to be
+/// consistent when both are present, so a key whose `key_ops` contains an
+/// operation outside of its `use` is rejected as [`Error::Invalid`]. Such a
+/// JWK already fails to deserialize, so this only catches parameters which
+/// are built in code.
+///
+/// Accessing the key for an operation which is not permitted fails with
+/// [`Error::Forbidden`].
+pub struct Restricted<K = Key> {
+    key: K,
+    ops: BTreeSet<Operations>,
+}
+
+impl<K> Restricted<K> {
+    /// Restricts the key to the operations permitted by the parameters.
+    pub fn new(key: K, prm: &Parameters) -> Result<Self, Error> {
+        if !prm.consistent() {
+            return Err(Error::Invalid);
+        }
+
+        let ops = match (prm.cls, &prm.ops) {
+            (_, Some(ops)) => ops.clone(),
+            (Some(cls), None) => cls.ops().iter().copied().collect(),
+            (None, None) => [Class::Signing, Class::Encryption]
+                .into_iter()
+                .flat_map(Class::ops)
+                .copied()
+                .collect(),
+        };
+
+        Ok(Self { key, ops })
+    }
+
+    /// Returns the permitted operations.
+    pub fn ops(&self) -> &BTreeSet<Operations> {
+        &self.ops
+    }
+
+    /// Returns whether the operation is permitted.
+    pub fn permits(&self, op: Operations) -> bool {
+        self.ops.contains(&op)
+    }
+
+    /// Returns the key if the operation is permitted.
+    pub fn get(&self, op: Operations) -> Result<&K, Error> {
+        match self.permits(op) {
+            true => Ok(&self.key),
+            false => Err(Error::Forbidden),
+        }
+    }
+
+    /// Returns the key if it may protect a content encryption key with the
+    /// key management algorithm.
+    ///
+    /// Direct encryption needs the `encrypt` operation, key agreement needs
+    /// `deriveKey` and all other algorithms need `wrapKey`.
+    pub fn wrapping(&self, alg: KeyManagement) -> Result<&K, Error> {
+        match alg {
+            KeyManagement::Direct => self.get(Operations::Encrypt),
+            KeyManagement::EcdhEs
+            | KeyManagement::EcdhEsA128Kw
+            | KeyManagement::EcdhEsA192Kw
+            | KeyManagement::EcdhEsA256Kw => self.get(Operations::DeriveKey),
+            _ => self.get(Operations::WrapKey),
+        }
+    }
+
+    /// Returns the key if it may recover a content encryption key with the
+    /// key management algorithm.
+    ///
+    /// Direct encryption needs the `decrypt` operation, key agreement needs
+    /// `deriveKey` and all other algorithms need `unwrapKey`.
+    pub fn unwrapping(&self, alg: KeyManagement) -> Result<&K, Error> {
+        match alg {
+            KeyManagement::Direct => self.get(Operations::Decrypt),
+            KeyManagement::EcdhEs
+            | KeyManagement::EcdhEsA128Kw
+            | KeyManagement::EcdhEsA192Kw
+            | KeyManagement::EcdhEsA256Kw => self.get(Operations::DeriveKey),
+            _ => self.get(Operations::UnwrapKey),
+        }
+    }
+
+    /// Returns the key, ignoring its restrictions.
+    pub fn into_inner(self) -> K {
+        self.key
+    }
+}
+
+impl TryFrom<&Jwk> for Restricted<Key> {
+    type Error = Error;
+
+    fn try_from(value: &Jwk) -> Result<Self, Self::Error> {
+        Self::new(Key::try_from(&value.key)?, &value.prm)
+    }
+}
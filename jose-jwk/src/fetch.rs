@@ -0,0 +1,255 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Fetching and caching of remote JWK Sets
+//!
+//! A [`RemoteJwkSet`] loads the JWK Set published at a URL (e.g. the
+//! `jwks_uri` of an OpenID provider) through a [`Transport`]. Requests are
+//! left to the transport, so that any HTTP client can be used; with the
+//! `reqwest` feature, [`reqwest::blocking::Client`] is a transport.
+//!
+//! Time is provided by the caller, as a duration since any fixed instant
+//! (such as the Unix epoch), so that no clock is needed.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use url::Url;
+
+use crate::{Jwk, JwkSet};
+
+/// The response to a request for a JWK Set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+
+    /// The value of the `Cache-Control` header, if any.
+    pub cache_control: Option<String>,
+
+    /// The response body.
+    pub body: Vec<u8>,
+}
+
+/// A transport which performs the requests for JWK Sets.
+pub trait Transport {
+    /// The error of a failed request.
+    type Error;
+
+    /// Sends a `GET` request for the URL.
+    fn fetch(&self, url: &Url) -> Result<Response, Self::Error>;
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for reqwest::blocking::Client {
+    type Error = reqwest::Error;
+
+    fn fetch(&self, url: &Url) -> Result<Response, Self::Error> {
+        let res = self.get(url.as_str()).send()?;
+        let status = res.status().as_u16();
+        let cache_control = res
+            .headers()
+            .get(reqwest::header::CACHE_CONTROL)
+            .and_then(|x| x.to_str().ok())
+            .map(String::from);
+
+        Ok(Response {
+            status,
+            cache_control,
+            body: res.bytes()?.to_vec(),
+        })
+    }
+}
+
+/// An error which occurred while fetching a JWK Set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error<E> {
+    /// The request failed.
+    Transport(E),
+
+    /// The response has an unsuccessful status code.
+    Status(u16),
+
+    /// The response body is not a JWK Set.
+    Malformed,
+
+    /// No JWK Set is cached and the last request was too recent to retry.
+    Backoff,
+}
+
+impl<E> Error<E> {
+    /// Whether a later request may succeed, so that stale keys can be used
+    /// meanwhile.
+    fn is_transient(&self) -> bool {
+        matches!(self, Self::Transport(..) | Self::Status(429 | 500..=599))
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(e) => write!(f, "request failed: {e}"),
+            Self::Status(status) => write!(f, "unsuccessful status code: {status}"),
+            Self::Malformed => f.write_str("response is not a JWK Set"),
+            Self::Backoff => f.write_str("request attempted too recently"),
+        }
+    }
+}
+
+/// How long remote JWK Sets are cached.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// How long a JWK Set is cached when the response has no `max-age`
+    /// directive.
+    pub default_ttl: Duration,
+
+    /// The shortest time a JWK Set is cached, even when the response has the
+    /// `no-cache` or `no-store` directive.
+    pub min_ttl: Duration,
+
+    /// The longest time a JWK Set is cached.
+    pub max_ttl: Duration,
+
+    /// The shortest time between two requests. This limits the requests
+    /// caused by unknown key IDs and by failing servers.
+    pub cooldown: Duration,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            default_ttl: Duration::from_secs(5 * 60),
+            min_ttl: Duration::from_secs(60),
+            max_ttl: Duration::from_secs(24 * 60 * 60),
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Computes how long a response is cached from its `Cache-Control`
+    /// header (RFC 9111 Section 5.2).
+    fn ttl(&self, cache_control: Option<&str>) -> Duration {
+        let mut ttl = self.default_ttl;
+
+        for dir in cache_control.into_iter().flat_map(|x| x.split(',')) {
+            let (name, value) = dir.split_once('=').unwrap_or((dir, ""));
+            let name = name.trim();
+
+            if name.eq_ignore_ascii_case("no-cache") || name.eq_ignore_ascii_case("no-store") {
+                return self.min_ttl;
+            }
+
+            if name.eq_ignore_ascii_case("max-age") {
+                if let Ok(secs) = value.trim().trim_matches('"').parse() {
+                    ttl = Duration::from_secs(secs);
+                }
+            }
+        }
+
+        ttl.max(self.min_ttl).min(self.max_ttl)
+    }
+}
+
+/// Finds the key with the key ID.
+fn lookup<'a>(jwks: &'a JwkSet, kid: &str) -> Option<&'a Jwk> {
+    jwks.keys.iter().find(|x| x.prm.kid.as_deref() == Some(kid))
+}
+
+/// A JWK Set fetched from a URL and cached.
+///
+/// The JWK Set is fetched again once the cached one expires, as allowed by
+/// the [`CachePolicy`], and when a key ID is not found in it. Requests are
+/// never closer together than the cooldown of the policy.
+///
+/// When a request fails with a transport error or with a `429` or `5xx`
+/// status code, the cached JWK Set, even if expired, is used until a later
+/// request succeeds. Other failures are returned.
+pub struct RemoteJwkSet<T> {
+    url: Url,
+    transport: T,
+    policy: CachePolicy,
+    jwks: Option<JwkSet>,
+    expires: Duration,
+    attempted: Option<Duration>,
+}
+
+impl<T: Transport> RemoteJwkSet<T> {
+    /// Creates the remote JWK Set. Nothing is fetched until it is used.
+    pub fn new(url: Url, transport: T, policy: CachePolicy) -> Self {
+        Self {
+            url,
+            transport,
+            policy,
+            jwks: None,
+            expires: Duration::ZERO,
+            attempted: None,
+        }
+    }
+
+    /// Returns the URL of the JWK Set.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the cached JWK Set, if any, without fetching it.
+    pub fn cached(&self) -> Option<&JwkSet> {
+        self.jwks.as_ref()
+    }
+
+    /// Fetches the JWK Set, replacing the cached one.
+    fn refresh(&mut self, now: Duration) -> Result<(), Error<T::Error>> {
+        self.attempted = Some(now);
+
+        let res = self.transport.fetch(&self.url).map_err(Error::Transport)?;
+        if !(200..300).contains(&res.status) {
+            return Err(Error::Status(res.status));
+        }
+
+        let jwks = serde_json::from_slice(&res.body).map_err(|_| Error::Malformed)?;
+        self.expires = now.saturating_add(self.policy.ttl(res.cache_control.as_deref()));
+        self.jwks = Some(jwks);
+        Ok(())
+    }
+
+    /// Fetches the JWK Set unless the cooldown forbids it, keeping the cached
+    /// one on transient errors.
+    fn update(&mut self, now: Duration) -> Result<(), Error<T::Error>> {
+        let cooldown = self
+            .attempted
+            .map_or(false, |x| now < x.saturating_add(self.policy.cooldown));
+
+        match cooldown {
+            true if self.jwks.is_some() => Ok(()),
+            true => Err(Error::Backoff),
+            false => match self.refresh(now) {
+                Err(e) if self.jwks.is_some() && e.is_transient() => Ok(()),
+                res => res,
+            },
+        }
+    }
+
+    /// Returns the JWK Set, fetching it if none is cached or if the cached one
+    /// has expired.
+    pub fn keys(&mut self, now: Duration) -> Result<&JwkSet, Error<T::Error>> {
+        if self.jwks.is_none() || now >= self.expires {
+            self.update(now)?;
+        }
+
+        self.jwks.as_ref().ok_or(Error::Backoff)
+    }
+
+    /// Finds the key with the key ID (`kid`), fetching the JWK Set again if
+    /// the key is not in the cached one (e.g. after a key rotation).
+    pub fn find(&mut self, kid: &str, now: Duration) -> Result<Option<&Jwk>, Error<T::Error>> {
+        if lookup(self.keys(now)?, kid).is_none() {
+            self.update(now)?;
+        }
+
+        let jwks = self.jwks.as_ref().ok_or(Error::Backoff)?;
+        Ok(lookup(jwks, kid))
+    }
+}
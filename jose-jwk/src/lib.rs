@@ -22,16 +22,25 @@
 extern crate alloc;
 
 pub mod crypto;
+#[cfg(feature = "fetch")]
+pub mod fetch;
 
 mod key;
+mod pkcs;
 mod prm;
+mod thumbprint;
 
 pub use key::*;
 pub use prm::{Class, Operations, Parameters, Thumbprint};
+#[cfg(feature = "thumbprint")]
+pub use thumbprint::ThumbprintHash;
 
 pub use jose_b64;
 pub use jose_jwa;
 
+#[cfg(feature = "pkcs8")]
+pub use {pkcs1, pkcs8, sec1};
+
 use serde::{Deserialize, Serialize};
 
 /// A set of JSON Web Keys.
@@ -0,0 +1,485 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! PKCS #8 and SubjectPublicKeyInfo encodings of JWK key material.
+//!
+//! The SEC1 and PKCS #1 encodings, PEM and encrypted PKCS #8 are provided by
+//! the blanket implementations of the `sec1`, `pkcs1` and `pkcs8` traits.
+
+#![cfg(feature = "pkcs8")]
+
+use alloc::vec::Vec;
+
+use pkcs1::{RsaPrivateKey, RsaPublicKey};
+use pkcs8::der::asn1::{AnyRef, OctetStringRef, UintRef};
+use pkcs8::der::{Decode, Encode, SecretDocument};
+use pkcs8::spki::{
+    self, AlgorithmIdentifierRef, Document, EncodePublicKey, SubjectPublicKeyInfoRef,
+};
+use pkcs8::{EncodePrivateKey, ObjectIdentifier, PrivateKeyInfo};
+use sec1::EcPrivateKey;
+use zeroize::Zeroizing;
+
+use crate::{Ec, EcCurves, Jwk, Key, Okp, OkpCurves, Rsa, RsaOptional, RsaOtherPrimes, RsaPrivate};
+
+/// `id-ecPublicKey` (RFC 5480)
+const EC: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+
+/// `rsaEncryption` (RFC 8017)
+const RSA: ObjectIdentifier = pkcs1::ALGORITHM_OID;
+
+const P256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+const P384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.34");
+const P521: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.35");
+const P256K: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.10");
+
+/// The CFRG curves (RFC 8410)
+const X25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.110");
+const X448: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.111");
+const ED25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+const ED448: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.113");
+
+impl EcCurves {
+    fn oid(&self) -> &'static ObjectIdentifier {
+        match self {
+            Self::P256 => &P256,
+            Self::P384 => &P384,
+            Self::P521 => &P521,
+            Self::P256K => &P256K,
+        }
+    }
+
+    fn from_oid(oid: ObjectIdentifier) -> spki::Result<Self> {
+        match oid {
+            P256 => Ok(Self::P256),
+            P384 => Ok(Self::P384),
+            P521 => Ok(Self::P521),
+            P256K => Ok(Self::P256K),
+            oid => Err(spki::Error::OidUnknown { oid }),
+        }
+    }
+
+    /// The length of a coordinate or a private key, in bytes.
+    fn len(&self) -> usize {
+        match self {
+            Self::P256 | Self::P256K => 32,
+            Self::P384 => 48,
+            Self::P521 => 66,
+        }
+    }
+}
+
+impl OkpCurves {
+    fn oid(&self) -> ObjectIdentifier {
+        match self {
+            Self::Ed25519 => ED25519,
+            Self::Ed448 => ED448,
+            Self::X25519 => X25519,
+            Self::X448 => X448,
+        }
+    }
+
+    fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        match oid {
+            ED25519 => Some(Self::Ed25519),
+            ED448 => Some(Self::Ed448),
+            X25519 => Some(Self::X25519),
+            X448 => Some(Self::X448),
+            _ => None,
+        }
+    }
+
+    /// The length of a public or private key, in bytes.
+    fn len(&self) -> usize {
+        match self {
+            Self::Ed25519 | Self::X25519 => 32,
+            Self::Ed448 => 57,
+            Self::X448 => 56,
+        }
+    }
+}
+
+/// Returns the algorithm identifier of the key type.
+fn algorithm(key: &Key) -> spki::Result<AlgorithmIdentifierRef<'static>> {
+    let (oid, parameters) = match key {
+        Key::Ec(ec) => (EC, Some(AnyRef::from(ec.crv.oid()))),
+        Key::Rsa(..) => (RSA, Some(AnyRef::NULL)),
+        Key::Okp(okp) => (okp.crv.oid(), None),
+        Key::Oct(..) => return Err(spki::Error::KeyMalformed),
+    };
+
+    Ok(AlgorithmIdentifierRef { oid, parameters })
+}
+
+/// Encodes the public key as an uncompressed SEC1 point.
+fn point(ec: &Ec) -> spki::Result<Vec<u8>> {
+    let len = ec.crv.len();
+    if ec.x.len() != len || ec.y.len() != len {
+        return Err(spki::Error::KeyMalformed);
+    }
+
+    Ok([&[0x04], &ec.x[..], &ec.y[..]].concat())
+}
+
+/// Decodes an uncompressed SEC1 point.
+fn coordinates(crv: EcCurves, point: &[u8]) -> spki::Result<(&[u8], &[u8])> {
+    match point {
+        [0x04, xy @ ..] if xy.len() == crv.len() * 2 => Ok(xy.split_at(crv.len())),
+        _ => Err(spki::Error::KeyMalformed),
+    }
+}
+
+/// Derives the public key of an EC private key without one.
+#[cfg_attr(
+    not(all(feature = "p256", feature = "p384", feature = "p521", feature = "k256")),
+    allow(unused_variables)
+)]
+fn derive_ec(crv: EcCurves, d: &[u8]) -> Option<Ec> {
+    match crv {
+        #[cfg(feature = "p256")]
+        EcCurves::P256 => p256::SecretKey::from_slice(d).ok().map(|x| Ec::from(&x)),
+
+        #[cfg(feature = "p384")]
+        EcCurves::P384 => p384::SecretKey::from_slice(d).ok().map(|x| Ec::from(&x)),
+
+        #[cfg(feature = "p521")]
+        EcCurves::P521 => p521::SecretKey::from_slice(d).ok().map(|x| Ec::from(&x)),
+
+        #[cfg(feature = "k256")]
+        EcCurves::P256K => k256::SecretKey::from_slice(d).ok().map(|x| Ec::from(&x)),
+
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Derives the public key of a CFRG private key without one.
+#[cfg_attr(
+    not(all(feature = "ed25519", feature = "ed448")),
+    allow(unused_variables)
+)]
+fn derive_okp(crv: OkpCurves, d: &[u8]) -> Option<Okp> {
+    match crv {
+        #[cfg(feature = "ed25519")]
+        OkpCurves::Ed25519 => ed25519_dalek::SigningKey::try_from(d)
+            .ok()
+            .map(|x| Okp::from(&x)),
+
+        #[cfg(feature = "ed448")]
+        OkpCurves::Ed448 => ed448_goldilocks_plus::SigningKey::try_from(d)
+            .ok()
+            .map(|x| Okp::from(&x)),
+
+        _ => None,
+    }
+}
+
+impl EncodePublicKey for Key {
+    fn to_public_key_der(&self) -> spki::Result<Document> {
+        let key = match self {
+            Key::Ec(ec) => point(ec)?,
+            Key::Rsa(rsa) => RsaPublicKey {
+                modulus: UintRef::new(&rsa.n)?,
+                public_exponent: UintRef::new(&rsa.e)?,
+            }
+            .to_der()?,
+            Key::Okp(okp) if okp.x.len() == okp.crv.len() => okp.x.to_vec(),
+            _ => return Err(spki::Error::KeyMalformed),
+        };
+
+        let spki = SubjectPublicKeyInfoRef {
+            algorithm: algorithm(self)?,
+            subject_public_key: key[..].try_into()?,
+        };
+
+        Ok(Document::encode_msg(&spki)?)
+    }
+}
+
+impl TryFrom<SubjectPublicKeyInfoRef<'_>> for Key {
+    type Error = spki::Error;
+
+    fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+        let key = spki
+            .subject_public_key
+            .as_bytes()
+            .ok_or(spki::Error::KeyMalformed)?;
+
+        match spki.algorithm.oid {
+            EC => {
+                let crv = EcCurves::from_oid(spki.algorithm.parameters_oid()?)?;
+                let (x, y) = coordinates(crv, key)?;
+
+                Ok(Self::Ec(Ec {
+                    crv,
+                    x: x.to_vec().into(),
+                    y: y.to_vec().into(),
+                    d: None,
+                }))
+            }
+
+            RSA => {
+                let rsa = RsaPublicKey::from_der(key)?;
+
+                Ok(Self::Rsa(Rsa {
+                    n: rsa.modulus.as_bytes().to_vec().into(),
+                    e: rsa.public_exponent.as_bytes().to_vec().into(),
+                    prv: None,
+                }))
+            }
+
+            oid => match OkpCurves::from_oid(oid) {
+                Some(crv) if key.len() == crv.len() => Ok(Self::Okp(Okp {
+                    crv,
+                    x: key.to_vec().into(),
+                    d: None,
+                })),
+                Some(..) => Err(spki::Error::KeyMalformed),
+                None => Err(spki::Error::OidUnknown { oid }),
+            },
+        }
+    }
+}
+
+impl EncodePrivateKey for Key {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+        let private_key = Zeroizing::new(match self {
+            Key::Ec(ec) => {
+                let d = ec.d.as_ref().ok_or(pkcs8::Error::KeyMalformed)?;
+                if d.len() != ec.crv.len() {
+                    return Err(pkcs8::Error::KeyMalformed);
+                }
+
+                // The curve is named by the algorithm identifier.
+                EcPrivateKey {
+                    private_key: d,
+                    parameters: None,
+                    public_key: Some(&point(ec)?),
+                }
+                .to_der()?
+            }
+
+            Key::Rsa(rsa) => {
+                let prv = rsa.prv.as_ref().ok_or(pkcs8::Error::KeyMalformed)?;
+                let opt = prv.opt.as_ref().ok_or(pkcs8::Error::KeyMalformed)?;
+
+                let oth = opt
+                    .oth
+                    .iter()
+                    .map(|x| {
+                        Ok(pkcs1::OtherPrimeInfo {
+                            prime: UintRef::new(&x.r)?,
+                            exponent: UintRef::new(&x.d)?,
+                            coefficient: UintRef::new(&x.t)?,
+                        })
+                    })
+                    .collect::<pkcs8::der::Result<Vec<_>>>()?;
+
+                RsaPrivateKey {
+                    modulus: UintRef::new(&rsa.n)?,
+                    public_exponent: UintRef::new(&rsa.e)?,
+                    private_exponent: UintRef::new(&prv.d)?,
+                    prime1: UintRef::new(&opt.p)?,
+                    prime2: UintRef::new(&opt.q)?,
+                    exponent1: UintRef::new(&opt.dp)?,
+                    exponent2: UintRef::new(&opt.dq)?,
+                    coefficient: UintRef::new(&opt.qi)?,
+                    other_prime_infos: Some(oth).filter(|x| !x.is_empty()),
+                }
+                .to_der()?
+            }
+
+            Key::Okp(okp) => {
+                let d = okp.d.as_ref().ok_or(pkcs8::Error::KeyMalformed)?;
+                if d.len() != okp.crv.len() || okp.x.len() != okp.crv.len() {
+                    return Err(pkcs8::Error::KeyMalformed);
+                }
+
+                OctetStringRef::new(d)?.to_der()?
+            }
+
+            Key::Oct(..) => return Err(pkcs8::Error::KeyMalformed),
+        });
+
+        // RFC 8410 Section 7 allows the public key of a CFRG private key.
+        let public_key = match self {
+            Key::Okp(okp) => Some(&okp.x[..]),
+            _ => None,
+        };
+
+        let info = PrivateKeyInfo {
+            algorithm: algorithm(self)?,
+            private_key: &private_key,
+            public_key,
+        };
+
+        info.try_into()
+    }
+}
+
+impl TryFrom<PrivateKeyInfo<'_>> for Key {
+    type Error = pkcs8::Error;
+
+    fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+        match info.algorithm.oid {
+            EC => {
+                let crv = EcCurves::from_oid(info.algorithm.parameters_oid()?)?;
+                let ec = EcPrivateKey::from_der(info.private_key)?;
+
+                let named = ec.parameters.and_then(|x| x.named_curve());
+                if named.map_or(false, |x| x != *crv.oid()) {
+                    return Err(pkcs8::Error::ParametersMalformed);
+                }
+
+                if ec.private_key.len() != crv.len() {
+                    return Err(pkcs8::Error::KeyMalformed);
+                }
+
+                match ec.public_key.or(info.public_key) {
+                    Some(point) => {
+                        let (x, y) = coordinates(crv, point)?;
+
+                        // When the backend is enabled, the embedded public
+                        // key must belong to the private key.
+                        if let Some(derived) = derive_ec(crv, ec.private_key) {
+                            if derived.x[..] != *x || derived.y[..] != *y {
+                                return Err(pkcs8::Error::KeyMalformed);
+                            }
+                        }
+
+                        Ok(Self::Ec(Ec {
+                            crv,
+                            x: x.to_vec().into(),
+                            y: y.to_vec().into(),
+                            d: Some(ec.private_key.to_vec().into()),
+                        }))
+                    }
+
+                    None => derive_ec(crv, ec.private_key)
+                        .map(Self::Ec)
+                        .ok_or(pkcs8::Error::KeyMalformed),
+                }
+            }
+
+            RSA => {
+                let rsa = RsaPrivateKey::from_der(info.private_key)?;
+
+                let oth = rsa
+                    .other_prime_infos
+                    .iter()
+                    .flatten()
+                    .map(|x| RsaOtherPrimes {
+                        r: x.prime.as_bytes().to_vec().into(),
+                        d: x.exponent.as_bytes().to_vec().into(),
+                        t: x.coefficient.as_bytes().to_vec().into(),
+                    })
+                    .collect();
+
+                Ok(Self::Rsa(Rsa {
+                    n: rsa.modulus.as_bytes().to_vec().into(),
+                    e: rsa.public_exponent.as_bytes().to_vec().into(),
+                    prv: Some(RsaPrivate {
+                        d: rsa.private_exponent.as_bytes().to_vec().into(),
+                        opt: Some(RsaOptional {
+                            p: rsa.prime1.as_bytes().to_vec().into(),
+                            q: rsa.prime2.as_bytes().to_vec().into(),
+                            dp: rsa.exponent1.as_bytes().to_vec().into(),
+                            dq: rsa.exponent2.as_bytes().to_vec().into(),
+                            qi: rsa.coefficient.as_bytes().to_vec().into(),
+                            oth,
+                        }),
+                    }),
+                }))
+            }
+
+            oid => {
+                let crv = OkpCurves::from_oid(oid).ok_or(spki::Error::OidUnknown { oid })?;
+                let d = OctetStringRef::from_der(info.private_key)?.as_bytes();
+                if d.len() != crv.len() {
+                    return Err(pkcs8::Error::KeyMalformed);
+                }
+
+                // The public key of X25519 and X448 keys cannot be derived,
+                // so it is only checked for the others.
+                match info.public_key {
+                    Some(x) if derive_okp(crv, d).map_or(false, |okp| okp.x[..] != *x) => {
+                        Err(pkcs8::Error::KeyMalformed)
+                    }
+                    Some(x) if x.len() == crv.len() => Ok(Self::Okp(Okp {
+                        crv,
+                        x: x.to_vec().into(),
+                        d: Some(d.to_vec().into()),
+                    })),
+                    Some(..) => Err(pkcs8::Error::KeyMalformed),
+                    None => derive_okp(crv, d)
+                        .map(Self::Okp)
+                        .ok_or(pkcs8::Error::KeyMalformed),
+                }
+            }
+        }
+    }
+}
+
+impl EncodePublicKey for Jwk {
+    fn to_public_key_der(&self) -> spki::Result<Document> {
+        self.key.to_public_key_der()
+    }
+}
+
+impl TryFrom<SubjectPublicKeyInfoRef<'_>> for Jwk {
+    type Error = spki::Error;
+
+    fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+        Ok(Self {
+            key: spki.try_into()?,
+            prm: Default::default(),
+        })
+    }
+}
+
+impl EncodePrivateKey for Jwk {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+        self.key.to_pkcs8_der()
+    }
+}
+
+impl TryFrom<PrivateKeyInfo<'_>> for Jwk {
+    type Error = pkcs8::Error;
+
+    fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+        Ok(Self {
+            key: info.try_into()?,
+            prm: Default::default(),
+        })
+    }
+}
+
+impl EncodePublicKey for crate::crypto::Key {
+    fn to_public_key_der(&self) -> spki::Result<Document> {
+        Key::from(self).to_public_key_der()
+    }
+}
+
+impl TryFrom<SubjectPublicKeyInfoRef<'_>> for crate::crypto::Key {
+    type Error = spki::Error;
+
+    fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+        let key = Key::try_from(spki)?;
+        Self::try_from(&key).map_err(|_| spki::Error::KeyMalformed)
+    }
+}
+
+impl EncodePrivateKey for crate::crypto::Key {
+    fn to_pkcs8_der(&self) -> pkcs8::Result<SecretDocument> {
+        Key::from(self).to_pkcs8_der()
+    }
+}
+
+impl TryFrom<PrivateKeyInfo<'_>> for crate::crypto::Key {
+    type Error = pkcs8::Error;
+
+    fn try_from(info: PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+        let key = Key::try_from(info)?;
+        Self::try_from(&key).map_err(|_| pkcs8::Error::KeyMalformed)
+    }
+}
@@ -8,14 +8,28 @@ use alloc::collections::BTreeSet;
 use alloc::string::String;
 use alloc::vec::Vec;
 
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "thumbprint")]
+use sha1::Sha1;
+#[cfg(feature = "thumbprint")]
+use sha2::{Digest, Sha256};
 
 use jose_b64::base64ct::Base64;
 use jose_b64::serde::Bytes;
 use jose_jwa::Algorithm;
 
 /// JWK parameters unrelated to the key implementation
+///
+/// Deserialization fails when `use` and `key_ops` are both present but
+/// inconsistent, as required by RFC 7517 Section 4.3, i.e. when `key_ops`
+/// contains an operation outside of `use`.
+///
+/// With the `thumbprint` feature, deserialization also fails when `x5t` or
+/// `x5t#S256` is not the thumbprint of the first certificate of `x5c`.
+/// Without it, the thumbprints are not checked, so the same JSON may be
+/// accepted or rejected depending on the enabled features.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Parameters {
     /// The algorithm used with this key.
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -47,12 +61,59 @@ pub struct Parameters {
     pub x5t: Thumbprint,
 }
 
+#[cfg(feature = "thumbprint")]
+impl Parameters {
+    /// Sets `x5t` and `x5t#S256` to the thumbprints of the first certificate
+    /// of `x5c`, if any.
+    pub fn set_x5t(&mut self) {
+        if let Some(x5c) = self.x5c.as_deref() {
+            self.x5t = Thumbprint::from_x5c(x5c);
+        }
+    }
+}
+
+impl Parameters {
+    /// Whether `use` and `key_ops`, when both are present, are consistent
+    /// (RFC 7517 Section 4.3).
+    pub(crate) fn consistent(&self) -> bool {
+        match (self.cls, &self.ops) {
+            (Some(cls), Some(ops)) => ops.iter().all(|op| cls.ops().contains(op)),
+            _ => true,
+        }
+    }
+}
+
+// The derived implementations are inherent (see `serde(remote)`), so that
+// deserialization can check the parameters.
+impl Serialize for Parameters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Parameters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let prm = Self::deserialize(deserializer)?;
+        if !prm.consistent() {
+            return Err(D::Error::custom("key_ops is inconsistent with use"));
+        }
+
+        #[cfg(feature = "thumbprint")]
+        if !prm.x5t.matches(prm.x5c.as_deref().unwrap_or_default()) {
+            return Err(D::Error::custom("x5t does not match x5c"));
+        }
+
+        Ok(prm)
+    }
+}
+
 impl<T: Into<Algorithm>> From<T> for Parameters {
     fn from(value: T) -> Self {
         let alg = Some(value.into());
 
         let cls = match alg {
             Some(Algorithm::Signing(..)) => Some(Class::Signing),
+            Some(Algorithm::KeyManagement(..)) => Some(Class::Encryption),
             _ => None,
         };
 
@@ -76,6 +137,18 @@ pub enum Class {
     Signing,
 }
 
+impl Class {
+    /// The operations permitted by the key class.
+    pub(crate) fn ops(self) -> &'static [Operations] {
+        use Operations::*;
+
+        match self {
+            Self::Signing => &[Sign, Verify],
+            Self::Encryption => &[Decrypt, DeriveBits, DeriveKey, Encrypt, UnwrapKey, WrapKey],
+        }
+    }
+}
+
 /// Key operations (i.e. `key_use` in the RFC)
 // NOTE: Keep in lexicographical order.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -104,3 +177,31 @@ pub struct Thumbprint {
     #[serde(skip_serializing_if = "Option::is_none", rename = "x5t#S256", default)]
     pub s256: Option<Bytes<[u8; 32]>>,
 }
+
+#[cfg(feature = "thumbprint")]
+impl Thumbprint {
+    /// Computes the thumbprints of the first (leaf) certificate of a
+    /// DER-encoded X.509 certificate chain (`x5c`), if any.
+    pub fn from_x5c<T: AsRef<[u8]>>(x5c: &[T]) -> Self {
+        match x5c.first() {
+            Some(leaf) => Self {
+                s1: Some(<[u8; 20]>::from(Sha1::digest(leaf)).into()),
+                s256: Some(<[u8; 32]>::from(Sha256::digest(leaf)).into()),
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Whether the thumbprints which are present are those of the first
+    /// certificate of a DER-encoded X.509 certificate chain (`x5c`). Any
+    /// thumbprint matches an empty chain.
+    pub fn matches<T: AsRef<[u8]>>(&self, x5c: &[T]) -> bool {
+        if x5c.is_empty() {
+            return true;
+        }
+
+        let other = Self::from_x5c(x5c);
+        (self.s1.is_none() || self.s1 == other.s1)
+            && (self.s256.is_none() || self.s256 == other.s256)
+    }
+}
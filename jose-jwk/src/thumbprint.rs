@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! JWK thumbprints as described in RFC 7638 and RFC 9278
+
+#![cfg(feature = "thumbprint")]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use zeroize::Zeroizing;
+
+use crate::{EcCurves, Jwk, Key, OkpCurves};
+
+/// The hash algorithm used to compute a JWK thumbprint.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ThumbprintHash {
+    /// SHA-256
+    #[default]
+    Sha256,
+
+    /// SHA-384
+    Sha384,
+
+    /// SHA-512
+    Sha512,
+}
+
+impl ThumbprintHash {
+    /// The name of the hash in the IANA Named Information Hash Algorithm
+    /// Registry, as used by RFC 9278.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha-256",
+            Self::Sha384 => "sha-384",
+            Self::Sha512 => "sha-512",
+        }
+    }
+}
+
+/// Hashes the canonical JSON form of a key's required members.
+///
+/// Members must be added in lexicographic order. Since all required member
+/// values are either base64url strings or fixed identifiers, no escaping is
+/// needed.
+struct Canonical<D>(D, bool);
+
+impl<D: Digest> Canonical<D> {
+    fn new() -> Self {
+        Self(D::new(), true)
+    }
+
+    fn member(mut self, name: &str, value: &str) -> Self {
+        self.0.update(if self.1 { "{\"" } else { ",\"" });
+        self.0.update(name);
+        self.0.update("\":\"");
+        self.0.update(value);
+        self.0.update("\"");
+        self.1 = false;
+        self
+    }
+
+    fn bytes(self, name: &str, value: &[u8]) -> Self {
+        let value = Zeroizing::new(Base64UrlUnpadded::encode_string(value));
+        self.member(name, &value)
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.0.update("}");
+        self.0.finalize().to_vec()
+    }
+}
+
+impl Key {
+    fn canonical<D: Digest>(&self) -> Vec<u8> {
+        match self {
+            Self::Ec(ec) => {
+                let crv = match ec.crv {
+                    EcCurves::P256 => "P-256",
+                    EcCurves::P384 => "P-384",
+                    EcCurves::P521 => "P-521",
+                    EcCurves::P256K => "secp256k1",
+                };
+
+                Canonical::<D>::new()
+                    .member("crv", crv)
+                    .member("kty", "EC")
+                    .bytes("x", &ec.x)
+                    .bytes("y", &ec.y)
+                    .finish()
+            }
+
+            Self::Rsa(rsa) => Canonical::<D>::new()
+                .bytes("e", &rsa.e)
+                .member("kty", "RSA")
+                .bytes("n", &rsa.n)
+                .finish(),
+
+            Self::Oct(oct) => Canonical::<D>::new()
+                .bytes("k", &oct.k)
+                .member("kty", "oct")
+                .finish(),
+
+            Self::Okp(okp) => {
+                let crv = match okp.crv {
+                    OkpCurves::Ed25519 => "Ed25519",
+                    OkpCurves::Ed448 => "Ed448",
+                    OkpCurves::X25519 => "X25519",
+                    OkpCurves::X448 => "X448",
+                };
+
+                Canonical::<D>::new()
+                    .member("crv", crv)
+                    .member("kty", "OKP")
+                    .bytes("x", &okp.x)
+                    .finish()
+            }
+        }
+    }
+
+    /// Computes the JWK thumbprint of this key.
+    ///
+    /// This is defined in [RFC7638 Section 3]. Only the required public
+    /// members of the key are hashed, so a private key and its public key
+    /// share the same thumbprint.
+    ///
+    /// [RFC7638 Section 3]: https://datatracker.ietf.org/doc/html/rfc7638#section-3
+    pub fn thumbprint(&self, hash: ThumbprintHash) -> Vec<u8> {
+        match hash {
+            ThumbprintHash::Sha256 => self.canonical::<Sha256>(),
+            ThumbprintHash::Sha384 => self.canonical::<Sha384>(),
+            ThumbprintHash::Sha512 => self.canonical::<Sha512>(),
+        }
+    }
+
+    /// Computes the JWK thumbprint URI of this key.
+    ///
+    /// This is defined in [RFC9278 Section 3], e.g.
+    /// `urn:ietf:params:oauth:jwk-thumbprint:sha-256:<thumbprint>`.
+    ///
+    /// [RFC9278 Section 3]: https://datatracker.ietf.org/doc/html/rfc9278#section-3
+    pub fn thumbprint_uri(&self, hash: ThumbprintHash) -> String {
+        let tp = Base64UrlUnpadded::encode_string(&self.thumbprint(hash));
+        alloc::format!(
+            "urn:ietf:params:oauth:jwk-thumbprint:{}:{}",
+            hash.name(),
+            tp
+        )
+    }
+}
+
+impl Jwk {
+    /// Computes the RFC 7638 JWK thumbprint of this key.
+    ///
+    /// See [`Key::thumbprint`].
+    pub fn thumbprint(&self, hash: ThumbprintHash) -> Vec<u8> {
+        self.key.thumbprint(hash)
+    }
+
+    /// Computes the RFC 9278 JWK thumbprint URI of this key.
+    ///
+    /// See [`Key::thumbprint_uri`].
+    pub fn thumbprint_uri(&self, hash: ThumbprintHash) -> String {
+        self.key.thumbprint_uri(hash)
+    }
+}
@@ -1,13 +1,15 @@
 // SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use alloc::string::{String, ToString};
 use core::fmt::Display;
 use core::{convert::Infallible, str::FromStr};
 
 use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use jose_b64::serde::Json;
 use jose_b64::stream::Error;
 
-use crate::{Flattened, General, Jws, Signature};
+use crate::{CompactError, Flattened, General, Jws, Protected, Signature};
 
 impl FromStr for Jws {
     type Err = Error<serde_json::Error>;
@@ -38,15 +40,20 @@ impl FromStr for Flattened {
             return Err(Error::Length);
         }
 
+        let protected: Json<Protected> = prot.parse()?;
+
+        // An empty payload is detached (RFC 7515 Appendix F). An unencoded
+        // payload (RFC 7797 Section 5.2) is used as-is.
         let payload = match payl {
             "" => None,
+            _ if !protected.b64 => Some(payl.as_bytes().to_vec().into()),
             _ => Some(payl.parse().map_err(|e: Error<Infallible>| e.cast())?),
         };
 
         Ok(Self {
             payload,
             signature: Signature {
-                protected: Some(prot.parse()?),
+                protected: Some(protected),
                 header: None,
                 signature: sign.parse().map_err(|e: Error<Infallible>| e.cast())?,
             },
@@ -54,16 +61,47 @@ impl FromStr for Flattened {
     }
 }
 
+/// Returns the unencoded payload (RFC 7797 Section 5.2) if it can be
+/// attached to the compact serialization.
+fn unencoded(payload: &[u8]) -> Option<&str> {
+    core::str::from_utf8(payload)
+        .ok()
+        .filter(|x| !x.contains('.'))
+}
+
+impl Flattened {
+    /// Emits the compact serialization.
+    ///
+    /// A JWS without a payload is emitted in the detached form, i.e. with an
+    /// empty payload segment. An unencoded payload (RFC 7797 Section 5.2)
+    /// must be UTF-8 and must not contain a period; otherwise this fails with
+    /// [`CompactError::Unencoded`] and the payload should be detached.
+    pub fn to_compact(&self) -> Result<String, CompactError> {
+        match self.payload.as_ref() {
+            Some(x) if !self.signature.b64() && unencoded(x).is_none() => {
+                Err(CompactError::Unencoded)
+            }
+            _ => Ok(self.to_string()),
+        }
+    }
+}
+
+/// Emits the compact serialization.
+///
+/// This behaves like [`Flattened::to_compact`], except that an unencoded
+/// payload which cannot be attached is emitted in the detached form.
 impl Display for Flattened {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let mut prot = alloc::string::String::new();
+        let mut prot = String::new();
         if let Some(x) = self.signature.protected.as_ref() {
             prot = Base64UrlUnpadded::encode_string(x.as_ref());
         }
 
-        let mut payl = alloc::string::String::new();
-        if let Some(x) = self.payload.as_ref() {
-            payl = Base64UrlUnpadded::encode_string(x);
+        let mut payl = String::new();
+        match self.payload.as_ref() {
+            Some(x) if !self.signature.b64() => payl.push_str(unencoded(x).unwrap_or_default()),
+            Some(x) => payl = Base64UrlUnpadded::encode_string(x),
+            None => (),
         }
 
         let sign = Base64UrlUnpadded::encode_string(&self.signature.signature);
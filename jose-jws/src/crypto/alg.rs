@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Algorithm allow-lists for verification
+
+use jose_jwa::Signing;
+
+use super::Error;
+use crate::{Flattened, General, Jws, Protected, Signature, Unprotected};
+
+/// Finds the algorithm in the JWS headers.
+///
+/// RFC 7515 Section 7.2.1 requires the headers to be disjoint, so `alg` must
+/// not appear in both.
+pub(super) fn algorithm(
+    prot: Option<&Protected>,
+    head: Option<&Unprotected>,
+) -> Result<Option<Signing>, Error> {
+    match (prot.and_then(|x| x.oth.alg), head.and_then(|x| x.alg)) {
+        (Some(..), Some(..)) => Err(Error::Malformed),
+        (alg, None) | (None, alg) => Ok(alg),
+    }
+}
+
+/// Checks the algorithm of a signature against an allow-list.
+///
+/// The `alg` header parameter is chosen by the creator of the JWS, so it
+/// must not decide on its own how a key is used. A signature without `alg`
+/// is malformed and one whose `alg` is not in `allowed` is rejected by
+/// policy. This includes `none` ([`Signing::Null`]), which is only accepted
+/// when listed explicitly.
+///
+/// The one-shot verification functions call this, as does
+/// [`VerifyingKey::verify_allowed`](super::VerifyingKey::verify_allowed)
+/// for the streaming interface.
+pub fn allowed(sig: &Signature, allowed: &[Signing]) -> Result<Signing, Error> {
+    let prot = sig.protected.as_deref();
+    let alg = algorithm(prot, sig.header.as_ref())?.ok_or(Error::Malformed)?;
+
+    match allowed.contains(&alg) {
+        true => Ok(alg),
+        false => Err(Error::Policy),
+    }
+}
+
+/// Values whose signature algorithms can be checked against an allow-list.
+///
+/// This is sealed; it is implemented for references to [`Signature`],
+/// [`Flattened`], [`General`] and [`Jws`].
+pub trait Allow: sealed::Sealed {
+    /// Checks every signature with [`allowed`].
+    fn allow(&self, algs: &[Signing]) -> Result<(), Error>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for &crate::Signature {}
+    impl Sealed for &crate::Flattened {}
+    impl Sealed for &crate::General {}
+    impl Sealed for &crate::Jws {}
+}
+
+impl Allow for &Signature {
+    fn allow(&self, algs: &[Signing]) -> Result<(), Error> {
+        allowed(self, algs).map(|_| ())
+    }
+}
+
+impl Allow for &Flattened {
+    fn allow(&self, algs: &[Signing]) -> Result<(), Error> {
+        (&self.signature).allow(algs)
+    }
+}
+
+impl Allow for &General {
+    fn allow(&self, algs: &[Signing]) -> Result<(), Error> {
+        self.signatures.iter().try_for_each(|sig| sig.allow(algs))
+    }
+}
+
+impl Allow for &Jws {
+    fn allow(&self, algs: &[Signing]) -> Result<(), Error> {
+        match self {
+            Jws::General(general) => general.allow(algs),
+            Jws::Flattened(flattened) => flattened.allow(algs),
+        }
+    }
+}
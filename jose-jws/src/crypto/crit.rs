@@ -0,0 +1,77 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Critical header parameters as described in RFC 7515 Section 4.1.11
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+use serde::de::IgnoredAny;
+
+use super::Error;
+use crate::Signature;
+
+/// Extensions understood by this crate.
+const BUILTIN: &[&str] = &["b64"];
+
+/// Header parameters defined by RFC 7515 and RFC 7518, which must not be
+/// listed as critical.
+const RESERVED: &[&str] = &[
+    "alg", "jku", "jwk", "kid", "x5u", "x5c", "x5t", "x5t#S256", "typ", "cty", "crit",
+];
+
+/// Checks the critical header parameters of a signature.
+///
+/// Each name in `crit` must be understood, either by this crate (`b64`) or
+/// because it appears in `understood`, and must be present in the JWS
+/// Protected Header. The list must not be empty, must not contain duplicates
+/// or header parameters defined by RFC 7515 and RFC 7518, and `crit` must not
+/// appear in the JWS Unprotected Header. When `b64` is `false`, it must be
+/// listed as critical (RFC 7797 Section 6).
+///
+/// The one-shot verification functions call this; users of the streaming
+/// [`VerifyingKey`](super::VerifyingKey) interface should call it themselves.
+pub fn critical(sig: &Signature, understood: &[&str]) -> Result<(), Error> {
+    if sig.header.as_ref().and_then(|x| x.crit.as_ref()).is_some() {
+        return Err(Error::Critical);
+    }
+
+    let prot = match sig.protected.as_ref() {
+        Some(prot) => prot,
+        None => return Ok(()),
+    };
+
+    if prot.oth.crit.is_some() {
+        return Err(Error::Critical);
+    }
+
+    let crit = match prot.crit.as_ref() {
+        Some(crit) if crit.is_empty() => return Err(Error::Critical),
+        Some(crit) => crit,
+        None if !prot.b64 => return Err(Error::Critical),
+        None => return Ok(()),
+    };
+
+    // RFC 7797 Section 6: an unencoded payload must be critical.
+    if !prot.b64 && !crit.iter().any(|x| x == "b64") {
+        return Err(Error::Critical);
+    }
+
+    // Extensions are unknown to `Protected`, so look at the raw header.
+    let raw: BTreeMap<String, IgnoredAny> =
+        serde_json::from_slice(prot.as_ref()).map_err(|_| Error::Malformed)?;
+
+    for (i, name) in crit.iter().enumerate() {
+        let name = name.as_str();
+
+        if crit[..i].iter().any(|x| x == name)
+            || RESERVED.contains(&name)
+            || !(BUILTIN.contains(&name) || understood.contains(&name))
+            || !raw.contains_key(name)
+        {
+            return Err(Error::Critical);
+        }
+    }
+
+    Ok(())
+}
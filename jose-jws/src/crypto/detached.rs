@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Streaming verification of a JWS with a detached payload.
+
+use alloc::vec::Vec;
+
+use jose_b64::stream::Update;
+use jose_jwa::Signing;
+
+use super::oneshot::{headers, parts};
+use super::{Error, Verifier, VerifyingKey};
+use crate::{Jws, Signature};
+
+/// Verifies a JWS with a detached payload (RFC 7515 Appendix F), streaming
+/// the payload.
+///
+/// The payload is passed in chunks through [`Update`] or, with the `std`
+/// feature, through [`std::io::Write`] or [`DetachedVerifier::read`]. It is
+/// base64url encoded on the fly as needed, so it never has to be held in
+/// memory. Any attached payload is ignored.
+pub struct DetachedVerifier<'a, V, E> {
+    /// Unsecured signatures have no verifier.
+    verifiers: Vec<(&'a Signature, Option<V>)>,
+    errors: Vec<E>,
+}
+
+impl<'a, V: Verifier<'a>, E> DetachedVerifier<'a, V, E> {
+    /// Begins verifying each signature of the JWS with the key.
+    ///
+    /// Signatures which cannot be verified with the key, whose algorithm is
+    /// not in `algs` or whose critical header parameters are not in `crit`
+    /// are skipped. These are handled as in [`verify`](super::verify).
+    pub fn new<K>(key: &'a K, jws: &'a Jws, algs: &[Signing], crit: &[&str]) -> Self
+    where
+        K: VerifyingKey<'a, &'a Signature, Verifier = V, StartError = E> + ?Sized,
+        E: From<Error>,
+    {
+        let mut verifiers = Vec::new();
+        let mut errors = Vec::new();
+
+        for sig in parts(jws).1 {
+            match headers(sig, algs, crit) {
+                Ok(true) => {
+                    verifiers.push((sig, None));
+                    continue;
+                }
+                Ok(false) => (),
+                Err(e) => {
+                    errors.push(e.into());
+                    continue;
+                }
+            }
+
+            match key.verify_allowed(sig, algs) {
+                Ok(verifier) => verifiers.push((sig, Some(verifier))),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        Self { verifiers, errors }
+    }
+
+    /// Finishes processing the payload.
+    ///
+    /// Returns the first signature which verifies; otherwise the errors of
+    /// all signatures are combined.
+    pub fn finish(self) -> Result<&'a Signature, E>
+    where
+        E: From<V::FinishError> + From<Vec<E>>,
+    {
+        let mut errors = self.errors;
+        for (sig, verifier) in self.verifiers {
+            match verifier.map_or(Ok(()), Verifier::finish) {
+                Ok(()) => return Ok(sig),
+                Err(e) => errors.push(e.into()),
+            }
+        }
+
+        Err(errors.into())
+    }
+}
+
+impl<'a, V: Verifier<'a>, E> Update for DetachedVerifier<'a, V, E> {
+    type Error = V::Error;
+
+    fn update(&mut self, chunk: impl AsRef<[u8]>) -> Result<(), Self::Error> {
+        for verifier in self.verifiers.iter_mut().filter_map(|x| x.1.as_mut()) {
+            verifier.update(chunk.as_ref())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, V, E> DetachedVerifier<'a, V, E>
+where
+    V: Verifier<'a>,
+    V::Error: std::error::Error + Send + Sync + 'static,
+{
+    /// Reads the payload from a reader until its end.
+    ///
+    /// Returns the number of bytes read.
+    pub fn read(&mut self, mut reader: impl std::io::Read) -> std::io::Result<u64> {
+        std::io::copy(&mut reader, self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, V, E> std::io::Write for DetachedVerifier<'a, V, E>
+where
+    V: Verifier<'a>,
+    V::Error: std::error::Error + Send + Sync + 'static,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! ECDSA signatures as described in RFC 7518 Section 3.4
+
+#![cfg(any(feature = "p256", feature = "p384", feature = "p521", feature = "k256"))]
+
+use alloc::vec::Vec;
+
+use rand_core::CryptoRngCore;
+use signature::hazmat::{PrehashVerifier, RandomizedPrehashSigner};
+use signature::SignatureEncoding;
+
+use super::Error;
+
+/// Signs a digest of the JWS Signing Input.
+///
+/// The signature is returned in the fixed-size `R || S` form.
+pub(super) fn sign<S: SignatureEncoding>(
+    key: &impl RandomizedPrehashSigner<S>,
+    prehash: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<Vec<u8>, Error> {
+    let sig = key
+        .sign_prehash_with_rng(rng, prehash)
+        .map_err(|_| Error::Malformed)?;
+    Ok(sig.to_bytes().as_ref().to_vec())
+}
+
+/// Verifies a fixed-size `R || S` signature over a digest of the JWS Signing
+/// Input.
+pub(super) fn verify<S: SignatureEncoding>(
+    key: &impl PrehashVerifier<S>,
+    prehash: &[u8],
+    sig: &[u8],
+) -> Result<(), Error> {
+    let sig = S::try_from(sig).map_err(|_| Error::Signature)?;
+    key.verify_prehash(prehash, &sig)
+        .map_err(|_| Error::Signature)
+}
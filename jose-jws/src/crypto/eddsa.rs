@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! EdDSA signatures as described in RFC 8037 Section 3.1
+//!
+//! Unlike the other algorithms, EdDSA signs the whole JWS Signing Input
+//! rather than a digest of it.
+
+#![cfg(any(feature = "ed25519", feature = "ed448"))]
+
+#[cfg(feature = "ed25519")]
+pub(super) mod ed25519 {
+    use alloc::vec::Vec;
+
+    use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+
+    use crate::crypto::Error;
+
+    /// Signs the JWS Signing Input.
+    pub(in crate::crypto) fn sign(key: &SigningKey, msg: &[u8]) -> Vec<u8> {
+        key.sign(msg).to_bytes().to_vec()
+    }
+
+    /// Verifies a signature over the JWS Signing Input.
+    ///
+    /// Strict verification rejects weak public keys and malleable signatures.
+    pub(in crate::crypto) fn verify(
+        key: &VerifyingKey,
+        msg: &[u8],
+        sig: &[u8],
+    ) -> Result<(), Error> {
+        let sig = Signature::from_slice(sig).map_err(|_| Error::Signature)?;
+        key.verify_strict(msg, &sig).map_err(|_| Error::Signature)
+    }
+}
+
+#[cfg(feature = "ed448")]
+pub(super) mod ed448 {
+    use alloc::vec::Vec;
+
+    use ed448_goldilocks_plus::{Signature, SigningKey, VerifyingKey};
+    use signature::Verifier;
+
+    use crate::crypto::Error;
+
+    /// Signs the JWS Signing Input.
+    pub(in crate::crypto) fn sign(key: &SigningKey, msg: &[u8]) -> Vec<u8> {
+        key.sign_raw(msg).to_bytes().to_vec()
+    }
+
+    /// Verifies a signature over the JWS Signing Input.
+    pub(in crate::crypto) fn verify(
+        key: &VerifyingKey,
+        msg: &[u8],
+        sig: &[u8],
+    ) -> Result<(), Error> {
+        let sig = Signature::try_from(sig).map_err(|_| Error::Signature)?;
+        key.verify(msg, &sig).map_err(|_| Error::Signature)
+    }
+}
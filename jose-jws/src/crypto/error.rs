@@ -0,0 +1,120 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Errors which occur while signing or verifying.
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt;
+
+/// An error which occurred while signing or verifying.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input is malformed, e.g. conflicting headers or a missing payload.
+    Malformed,
+
+    /// The algorithm is not supported (or its feature is not enabled).
+    Unsupported,
+
+    /// The key cannot be used with the algorithm.
+    AlgMismatch,
+
+    /// The key is too weak for the algorithm.
+    WeakKey,
+
+    /// The key's `use` or `key_ops` do not permit the operation.
+    Operation,
+
+    /// The private key is unknown.
+    NotPrivate,
+
+    /// No key matches the signature.
+    NoKey,
+
+    /// Several keys match the signature.
+    Ambiguous,
+
+    /// The signature does not match the payload and key.
+    Signature,
+
+    /// The `x5c` certificate chain is invalid, untrusted or does not match
+    /// the key.
+    Certificate,
+
+    /// The critical header parameters are not understood or are malformed.
+    Critical,
+
+    /// The JWS was rejected by the verification policy, e.g. its algorithm
+    /// is not allowed.
+    Policy,
+
+    /// None of the signatures verified.
+    ///
+    /// This holds the error of each attempted signature, in order.
+    Signatures(Vec<Error>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("malformed input"),
+            Self::Unsupported => f.write_str("unsupported algorithm"),
+            Self::AlgMismatch => f.write_str("key cannot be used with the algorithm"),
+            Self::WeakKey => f.write_str("key is too weak for the algorithm"),
+            Self::Operation => f.write_str("key does not permit the operation"),
+            Self::NotPrivate => f.write_str("private key is unknown"),
+            Self::NoKey => f.write_str("no key matches the signature"),
+            Self::Ambiguous => f.write_str("several keys match the signature"),
+            Self::Signature => f.write_str("signature mismatch"),
+            Self::Certificate => f.write_str("certificate chain rejected"),
+            Self::Critical => f.write_str("critical header parameters rejected"),
+            Self::Policy => f.write_str("rejected by policy"),
+            Self::Signatures(all) => {
+                f.write_str("no signature verified")?;
+                for (i, e) in all.iter().enumerate() {
+                    write!(f, "{} {i}: {e}", if i == 0 { ":" } else { ";" })?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<Infallible> for Error {
+    fn from(_: Infallible) -> Self {
+        unreachable!()
+    }
+}
+
+impl From<jose_jwk::crypto::Error> for Error {
+    fn from(value: jose_jwk::crypto::Error) -> Self {
+        use jose_jwk::crypto::Error as E;
+
+        match value {
+            E::Invalid => Self::Malformed,
+            E::NotPrivate => Self::NotPrivate,
+            E::AlgMismatch => Self::AlgMismatch,
+            E::Unsupported => Self::Unsupported,
+            E::Forbidden => Self::Operation,
+            _ => Self::Malformed,
+        }
+    }
+}
+
+/// Combines the errors of several signatures.
+///
+/// A single error is kept as-is, and a JWS without signatures is malformed.
+impl From<Vec<Error>> for Error {
+    fn from(mut value: Vec<Error>) -> Self {
+        match value.len() {
+            0 => Self::Malformed,
+            1 => value.remove(0),
+            _ => Self::Signatures(value),
+        }
+    }
+}
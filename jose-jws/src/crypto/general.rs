@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Signing with multiple keys in a single pass over the payload.
+
+use alloc::vec::Vec;
+
+use jose_b64::stream::Update;
+use rand_core::CryptoRngCore;
+
+use super::{Error, Signer, SigningKey};
+use crate::{General, Protected, Unprotected};
+
+/// A builder for a general JWS signed by several keys.
+///
+/// Each key gets its own protected and unprotected header. The payload is
+/// then passed once, in as many chunks as desired, through [`Update`] and is
+/// broadcast to every signer.
+///
+/// The payload is shared, so all keys must use the same `b64` header
+/// parameter (RFC 7797 Section 3).
+#[derive(Debug)]
+pub struct GeneralSigner<S> {
+    signers: Vec<S>,
+    payload: Vec<u8>,
+    b64: Option<bool>,
+}
+
+impl<S> Default for GeneralSigner<S> {
+    fn default() -> Self {
+        Self {
+            signers: Vec::new(),
+            payload: Vec::new(),
+            b64: None,
+        }
+    }
+}
+
+impl<S: Signer> GeneralSigner<S> {
+    /// Creates a builder without any signers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key with its headers.
+    ///
+    /// All keys must be added before the payload is processed. A key whose
+    /// `b64` header parameter differs from that of the keys already added is
+    /// rejected with [`Error::Malformed`].
+    pub fn add<'a, K>(
+        mut self,
+        key: &'a K,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self, K::StartError>
+    where
+        K: SigningKey<'a, Signer = S> + ?Sized,
+        K::StartError: From<Error>,
+    {
+        let b64 = prot.as_ref().map_or(true, |x| x.b64);
+        if self.b64.map_or(false, |x| x != b64) {
+            return Err(Error::Malformed.into());
+        }
+
+        self.signers.push(key.sign(prot, head)?);
+        self.b64 = Some(b64);
+        Ok(self)
+    }
+
+    /// Finishes all signatures, creating a general JWS.
+    ///
+    /// At least one key must have been added.
+    pub fn finish(self, mut rng: impl CryptoRngCore) -> Result<General, S::FinishError>
+    where
+        S::FinishError: From<Error>,
+    {
+        if self.signers.is_empty() {
+            return Err(Error::Malformed.into());
+        }
+
+        let signatures = self
+            .signers
+            .into_iter()
+            .map(|signer| signer.finish(&mut rng))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(General {
+            payload: Some(self.payload.into()),
+            signatures,
+        })
+    }
+}
+
+impl<S: Signer> Update for GeneralSigner<S> {
+    type Error = S::Error;
+
+    fn update(&mut self, chunk: impl AsRef<[u8]>) -> Result<(), Self::Error> {
+        self.signers.update(chunk.as_ref())?;
+        self.payload.extend_from_slice(chunk.as_ref());
+        Ok(())
+    }
+}
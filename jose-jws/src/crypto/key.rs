@@ -0,0 +1,882 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![cfg(any(
+    feature = "p256",
+    feature = "p384",
+    feature = "p521",
+    feature = "k256",
+    feature = "rsa",
+    feature = "hmac",
+    feature = "ed25519",
+    feature = "ed448"
+))]
+
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+#[cfg(feature = "hmac")]
+use alloc::boxed::Box;
+
+use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use jose_b64::serde::Json;
+use jose_b64::stream::{Optional, Update};
+use jose_jwa::{Algorithm, Signing};
+use jose_jwk::crypto::{Key, KeyInfo, Kind};
+use jose_jwk::Jwk;
+use rand_core::CryptoRngCore;
+use sha2::Digest;
+
+#[cfg(feature = "hmac")]
+use hmac::{Hmac, Mac};
+#[cfg(feature = "hmac")]
+use subtle::ConstantTimeEq;
+#[cfg(feature = "hmac")]
+use zeroize::Zeroizing;
+
+use super::alg::{algorithm, allowed};
+use super::resolve::permits;
+use super::{Error, Signer, SigningKey, Verifier, VerifyingKey};
+use crate::{Protected, Signature, Unprotected};
+
+/// A running digest (or MAC) of the JWS Signing Input.
+///
+/// EdDSA signs the JWS Signing Input itself, so it is buffered instead.
+enum Hash {
+    Sha256(sha2::Sha256),
+    Sha384(sha2::Sha384),
+    Sha512(sha2::Sha512),
+
+    #[cfg(feature = "hmac")]
+    Hs256(Hmac<sha2::Sha256>),
+
+    #[cfg(feature = "hmac")]
+    Hs384(Hmac<sha2::Sha384>),
+
+    #[cfg(feature = "hmac")]
+    Hs512(Hmac<sha2::Sha512>),
+
+    #[cfg(any(feature = "ed25519", feature = "ed448"))]
+    Raw(Vec<u8>),
+}
+
+impl Hash {
+    fn new(alg: Signing) -> Result<Self, Error> {
+        match alg {
+            Signing::Es256 | Signing::Es256K => Ok(Self::Sha256(Default::default())),
+            Signing::Es384 => Ok(Self::Sha384(Default::default())),
+            Signing::Es512 => Ok(Self::Sha512(Default::default())),
+            Signing::Rs256 | Signing::Ps256 => Ok(Self::Sha256(Default::default())),
+            Signing::Rs384 | Signing::Ps384 => Ok(Self::Sha384(Default::default())),
+            Signing::Rs512 | Signing::Ps512 => Ok(Self::Sha512(Default::default())),
+
+            #[cfg(any(feature = "ed25519", feature = "ed448"))]
+            Signing::EdDsa => Ok(Self::Raw(Vec::new())),
+
+            _ => Err(Error::Unsupported),
+        }
+    }
+
+    #[cfg(feature = "hmac")]
+    fn mac(alg: Signing, key: &[u8]) -> Result<Self, Error> {
+        match alg {
+            Signing::Hs256 => Ok(Self::Hs256(
+                Hmac::new_from_slice(key).map_err(|_| Error::WeakKey)?,
+            )),
+            Signing::Hs384 => Ok(Self::Hs384(
+                Hmac::new_from_slice(key).map_err(|_| Error::WeakKey)?,
+            )),
+            Signing::Hs512 => Ok(Self::Hs512(
+                Hmac::new_from_slice(key).map_err(|_| Error::WeakKey)?,
+            )),
+            _ => Err(Error::AlgMismatch),
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Sha256(x) => x.finalize().to_vec(),
+            Self::Sha384(x) => x.finalize().to_vec(),
+            Self::Sha512(x) => x.finalize().to_vec(),
+
+            #[cfg(feature = "hmac")]
+            Self::Hs256(x) => x.finalize().into_bytes().to_vec(),
+
+            #[cfg(feature = "hmac")]
+            Self::Hs384(x) => x.finalize().into_bytes().to_vec(),
+
+            #[cfg(feature = "hmac")]
+            Self::Hs512(x) => x.finalize().into_bytes().to_vec(),
+
+            #[cfg(any(feature = "ed25519", feature = "ed448"))]
+            Self::Raw(x) => x,
+        }
+    }
+}
+
+impl Update for Hash {
+    type Error = Infallible;
+
+    fn update(&mut self, chunk: impl AsRef<[u8]>) -> Result<(), Self::Error> {
+        match self {
+            Self::Sha256(x) => Digest::update(x, chunk),
+            Self::Sha384(x) => Digest::update(x, chunk),
+            Self::Sha512(x) => Digest::update(x, chunk),
+
+            #[cfg(feature = "hmac")]
+            Self::Hs256(x) => Mac::update(x, chunk.as_ref()),
+
+            #[cfg(feature = "hmac")]
+            Self::Hs384(x) => Mac::update(x, chunk.as_ref()),
+
+            #[cfg(feature = "hmac")]
+            Self::Hs512(x) => Mac::update(x, chunk.as_ref()),
+
+            #[cfg(any(feature = "ed25519", feature = "ed448"))]
+            Self::Raw(x) => x.extend_from_slice(chunk.as_ref()),
+        }
+
+        Ok(())
+    }
+}
+
+/// Explains why a key does not support an algorithm.
+///
+/// RSA and HMAC keys of the right type may still be too short for it.
+fn rejected(family: bool) -> Error {
+    match family {
+        true => Error::WeakKey,
+        false => Error::AlgMismatch,
+    }
+}
+
+#[cfg(feature = "rsa")]
+fn is_rsa(alg: Signing) -> bool {
+    use Signing::*;
+    matches!(alg, Rs256 | Rs384 | Rs512 | Ps256 | Ps384 | Ps512)
+}
+
+#[cfg(feature = "hmac")]
+fn is_hmac(alg: Signing) -> bool {
+    matches!(alg, Signing::Hs256 | Signing::Hs384 | Signing::Hs512)
+}
+
+/// Begins hashing the JWS Signing Input as described in RFC 7515 Section 5.1.
+///
+/// The returned state expects the unencoded payload.
+fn start(mut hash: Hash, prot: Option<&Json<Protected>>) -> Result<Optional<Hash>, Error> {
+    let mut b64 = true;
+    if let Some(prot) = prot {
+        hash.update(Base64UrlUnpadded::encode_string(prot.as_ref()))?;
+        b64 = prot.b64;
+    }
+
+    hash.update(".")?;
+    Ok(Optional::new(hash, b64))
+}
+
+/// A private key which can be used for signing.
+enum Secret<'a> {
+    #[cfg(feature = "p256")]
+    P256(&'a p256::SecretKey),
+
+    #[cfg(feature = "p384")]
+    P384(&'a p384::SecretKey),
+
+    #[cfg(feature = "p521")]
+    P521(&'a p521::SecretKey),
+
+    #[cfg(feature = "k256")]
+    P256K(&'a k256::SecretKey),
+
+    #[cfg(feature = "rsa")]
+    Rsa(&'a rsa::RsaPrivateKey),
+
+    #[cfg(feature = "hmac")]
+    Oct(&'a [u8]),
+
+    #[cfg(feature = "ed25519")]
+    Ed25519(&'a ed25519_dalek::SigningKey),
+
+    #[cfg(feature = "ed448")]
+    Ed448(&'a ed448_goldilocks_plus::SigningKey),
+}
+
+impl Secret<'_> {
+    /// Whether keys of this type are used with the algorithm.
+    #[cfg_attr(not(any(feature = "rsa", feature = "hmac")), allow(unused_variables))]
+    fn family(&self, alg: Signing) -> bool {
+        match self {
+            #[cfg(feature = "rsa")]
+            Self::Rsa(..) => is_rsa(alg),
+
+            #[cfg(feature = "hmac")]
+            Self::Oct(..) => is_hmac(alg),
+
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// The algorithm to use when none is specified.
+    fn alg(&self) -> Option<Signing> {
+        match self {
+            #[cfg(feature = "p256")]
+            Self::P256(..) => Some(Signing::Es256),
+
+            #[cfg(feature = "p384")]
+            Self::P384(..) => Some(Signing::Es384),
+
+            #[cfg(feature = "p521")]
+            Self::P521(..) => Some(Signing::Es512),
+
+            #[cfg(feature = "k256")]
+            Self::P256K(..) => Some(Signing::Es256K),
+
+            #[cfg(feature = "rsa")]
+            Self::Rsa(..) => None,
+
+            #[cfg(feature = "hmac")]
+            Self::Oct(..) => None,
+
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(..) => Some(Signing::EdDsa),
+
+            #[cfg(feature = "ed448")]
+            Self::Ed448(..) => Some(Signing::EdDsa),
+        }
+    }
+
+    /// Creates the running digest (or MAC) for the algorithm.
+    fn hash(&self, alg: Signing) -> Result<Hash, Error> {
+        match self {
+            #[cfg(feature = "hmac")]
+            Self::Oct(key) => Hash::mac(alg, key),
+
+            #[allow(unreachable_patterns)]
+            _ => Hash::new(alg),
+        }
+    }
+
+    #[cfg_attr(not(feature = "rsa"), allow(unused_variables))]
+    fn sign(
+        &self,
+        alg: Signing,
+        hash: Hash,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<Vec<u8>, Error> {
+        let digest = hash.finish();
+
+        match self {
+            #[cfg(feature = "p256")]
+            Self::P256(key) => {
+                let key = p256::ecdsa::SigningKey::from(*key);
+                super::ecdsa::sign::<p256::ecdsa::Signature>(&key, &digest, rng)
+            }
+
+            #[cfg(feature = "p384")]
+            Self::P384(key) => {
+                let key = p384::ecdsa::SigningKey::from(*key);
+                super::ecdsa::sign::<p384::ecdsa::Signature>(&key, &digest, rng)
+            }
+
+            #[cfg(feature = "p521")]
+            Self::P521(key) => {
+                let key = p521::ecdsa::SigningKey::from_bytes(&key.to_bytes())
+                    .map_err(|_| Error::Malformed)?;
+                super::ecdsa::sign::<p521::ecdsa::Signature>(&key, &digest, rng)
+            }
+
+            #[cfg(feature = "k256")]
+            Self::P256K(key) => {
+                let key = k256::ecdsa::SigningKey::from(*key);
+                super::ecdsa::sign::<k256::ecdsa::Signature>(&key, &digest, rng)
+            }
+
+            #[cfg(feature = "rsa")]
+            Self::Rsa(key) => super::rsa::sign(key, alg, &digest, rng),
+
+            #[cfg(feature = "hmac")]
+            Self::Oct(..) => Ok(digest),
+
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(key) => Ok(super::eddsa::ed25519::sign(key, &digest)),
+
+            #[cfg(feature = "ed448")]
+            Self::Ed448(key) => Ok(super::eddsa::ed448::sign(key, &digest)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a Key> for Secret<'a> {
+    type Error = Error;
+
+    fn try_from(value: &'a Key) -> Result<Self, Self::Error> {
+        #[allow(dead_code)]
+        fn secret<P, S>(kind: &Kind<P, S>) -> Result<&S, Error> {
+            match kind {
+                Kind::Public(..) => Err(Error::NotPrivate),
+                Kind::Secret(key) => Ok(key),
+            }
+        }
+
+        match value {
+            #[cfg(feature = "p256")]
+            Key::P256(kind) => Ok(Self::P256(secret(kind)?)),
+
+            #[cfg(feature = "p384")]
+            Key::P384(kind) => Ok(Self::P384(secret(kind)?)),
+
+            #[cfg(feature = "p521")]
+            Key::P521(kind) => Ok(Self::P521(secret(kind)?)),
+
+            #[cfg(feature = "k256")]
+            Key::P256K(kind) => Ok(Self::P256K(secret(kind)?)),
+
+            #[cfg(feature = "rsa")]
+            Key::Rsa(kind) => Ok(Self::Rsa(secret(kind)?)),
+
+            #[cfg(feature = "hmac")]
+            Key::Oct(key) => Ok(Self::Oct(key)),
+
+            #[cfg(feature = "ed25519")]
+            Key::Ed25519(kind) => Ok(Self::Ed25519(secret(kind)?)),
+
+            #[cfg(feature = "ed448")]
+            Key::Ed448(kind) => Ok(Self::Ed448(secret(kind)?)),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::Unsupported),
+        }
+    }
+}
+
+/// A public key which can be used for verification.
+enum Public {
+    #[cfg(feature = "p256")]
+    P256(p256::PublicKey),
+
+    #[cfg(feature = "p384")]
+    P384(p384::PublicKey),
+
+    #[cfg(feature = "p521")]
+    P521(p521::PublicKey),
+
+    #[cfg(feature = "k256")]
+    P256K(k256::PublicKey),
+
+    #[cfg(feature = "rsa")]
+    Rsa(rsa::RsaPublicKey),
+
+    #[cfg(feature = "hmac")]
+    Oct(Zeroizing<Box<[u8]>>),
+
+    #[cfg(feature = "ed25519")]
+    Ed25519(ed25519_dalek::VerifyingKey),
+
+    #[cfg(feature = "ed448")]
+    Ed448(ed448_goldilocks_plus::VerifyingKey),
+}
+
+impl Public {
+    /// Whether keys of this type are used with the algorithm.
+    #[cfg_attr(not(any(feature = "rsa", feature = "hmac")), allow(unused_variables))]
+    fn family(&self, alg: Signing) -> bool {
+        match self {
+            #[cfg(feature = "rsa")]
+            Self::Rsa(..) => is_rsa(alg),
+
+            #[cfg(feature = "hmac")]
+            Self::Oct(..) => is_hmac(alg),
+
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Creates the running digest (or MAC) for the algorithm.
+    fn hash(&self, alg: Signing) -> Result<Hash, Error> {
+        match self {
+            #[cfg(feature = "hmac")]
+            Self::Oct(key) => Hash::mac(alg, key),
+
+            #[allow(unreachable_patterns)]
+            _ => Hash::new(alg),
+        }
+    }
+
+    #[cfg_attr(not(feature = "rsa"), allow(unused_variables))]
+    fn verify(&self, alg: Signing, hash: Hash, sig: &[u8]) -> Result<(), Error> {
+        let digest = hash.finish();
+
+        match self {
+            #[cfg(feature = "p256")]
+            Self::P256(key) => {
+                let key = p256::ecdsa::VerifyingKey::from(key);
+                super::ecdsa::verify::<p256::ecdsa::Signature>(&key, &digest, sig)
+            }
+
+            #[cfg(feature = "p384")]
+            Self::P384(key) => {
+                let key = p384::ecdsa::VerifyingKey::from(key);
+                super::ecdsa::verify::<p384::ecdsa::Signature>(&key, &digest, sig)
+            }
+
+            #[cfg(feature = "p521")]
+            Self::P521(key) => {
+                let key = p521::ecdsa::VerifyingKey::from_affine(*key.as_affine())
+                    .map_err(|_| Error::Malformed)?;
+                super::ecdsa::verify::<p521::ecdsa::Signature>(&key, &digest, sig)
+            }
+
+            #[cfg(feature = "k256")]
+            Self::P256K(key) => {
+                let key = k256::ecdsa::VerifyingKey::from(key);
+                super::ecdsa::verify::<k256::ecdsa::Signature>(&key, &digest, sig)
+            }
+
+            #[cfg(feature = "rsa")]
+            Self::Rsa(key) => super::rsa::verify(key, alg, &digest, sig),
+
+            // The MAC must be compared in constant time.
+            #[cfg(feature = "hmac")]
+            Self::Oct(..) if bool::from(digest[..].ct_eq(sig)) => Ok(()),
+
+            #[cfg(feature = "hmac")]
+            Self::Oct(..) => Err(Error::Signature),
+
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(key) => super::eddsa::ed25519::verify(key, &digest, sig),
+
+            #[cfg(feature = "ed448")]
+            Self::Ed448(key) => super::eddsa::ed448::verify(key, &digest, sig),
+        }
+    }
+}
+
+impl TryFrom<&Key> for Public {
+    type Error = Error;
+
+    fn try_from(value: &Key) -> Result<Self, Self::Error> {
+        match value {
+            #[cfg(feature = "p256")]
+            Key::P256(Kind::Public(key)) => Ok(Self::P256(*key)),
+
+            #[cfg(feature = "p256")]
+            Key::P256(Kind::Secret(key)) => Ok(Self::P256(key.public_key())),
+
+            #[cfg(feature = "p384")]
+            Key::P384(Kind::Public(key)) => Ok(Self::P384(*key)),
+
+            #[cfg(feature = "p384")]
+            Key::P384(Kind::Secret(key)) => Ok(Self::P384(key.public_key())),
+
+            #[cfg(feature = "p521")]
+            Key::P521(Kind::Public(key)) => Ok(Self::P521(*key)),
+
+            #[cfg(feature = "p521")]
+            Key::P521(Kind::Secret(key)) => Ok(Self::P521(key.public_key())),
+
+            #[cfg(feature = "k256")]
+            Key::P256K(Kind::Public(key)) => Ok(Self::P256K(*key)),
+
+            #[cfg(feature = "k256")]
+            Key::P256K(Kind::Secret(key)) => Ok(Self::P256K(key.public_key())),
+
+            #[cfg(feature = "rsa")]
+            Key::Rsa(Kind::Public(key)) => Ok(Self::Rsa(key.clone())),
+
+            #[cfg(feature = "rsa")]
+            Key::Rsa(Kind::Secret(key)) => Ok(Self::Rsa(key.to_public_key())),
+
+            #[cfg(feature = "hmac")]
+            Key::Oct(key) => Ok(Self::Oct(key.clone())),
+
+            #[cfg(feature = "ed25519")]
+            Key::Ed25519(Kind::Public(key)) => Ok(Self::Ed25519(*key)),
+
+            #[cfg(feature = "ed25519")]
+            Key::Ed25519(Kind::Secret(key)) => Ok(Self::Ed25519(key.verifying_key())),
+
+            #[cfg(feature = "ed448")]
+            Key::Ed448(Kind::Public(key)) => Ok(Self::Ed448(*key)),
+
+            #[cfg(feature = "ed448")]
+            Key::Ed448(Kind::Secret(key)) => Ok(Self::Ed448(key.verifying_key())),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::Unsupported),
+        }
+    }
+}
+
+/// Verifies a signature over a complete message, such as the signed part of
+/// an X.509 certificate.
+#[cfg(feature = "x509")]
+pub(super) fn verify_message(key: &Key, alg: Signing, msg: &[u8], sig: &[u8]) -> Result<(), Error> {
+    let public = Public::try_from(key)?;
+    if !key.is_supported(&alg.into()) {
+        return Err(rejected(public.family(alg)));
+    }
+
+    let mut hash = public.hash(alg)?;
+    hash.update(msg)?;
+    public.verify(alg, hash, sig)
+}
+
+/// The signature creation state of the keys implemented by this crate.
+pub struct KeySigner<'a> {
+    key: Secret<'a>,
+    alg: Signing,
+    prot: Option<Json<Protected>>,
+    head: Option<Unprotected>,
+    hash: Optional<Hash>,
+}
+
+impl<'a> KeySigner<'a> {
+    fn new(
+        key: Secret<'a>,
+        info: &impl KeyInfo,
+        mut prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self, Error> {
+        // RFC 7515 Section 4.1.11: `crit` must be integrity protected.
+        if head.as_ref().and_then(|x| x.crit.as_ref()).is_some()
+            || prot.as_ref().and_then(|x| x.oth.crit.as_ref()).is_some()
+        {
+            return Err(Error::Critical);
+        }
+
+        let alg = match algorithm(prot.as_ref(), head.as_ref())? {
+            Some(alg) => alg,
+            None => {
+                let alg = key.alg().ok_or(Error::Malformed)?;
+                prot.get_or_insert_with(Protected::default).oth.alg = Some(alg);
+                alg
+            }
+        };
+
+        if !info.is_supported(&alg.into()) {
+            return Err(rejected(key.family(alg)));
+        }
+
+        // RFC 7797 Section 6: `b64` must be listed as critical.
+        if let Some(prot) = prot.as_mut().filter(|x| !x.b64) {
+            let crit = prot.crit.get_or_insert_with(Vec::new);
+            if !crit.iter().any(|x| x == "b64") {
+                crit.push("b64".into());
+            }
+        }
+
+        let prot = match prot {
+            Some(prot) => Some(Json::new(prot).map_err(|_| Error::Malformed)?),
+            None => None,
+        };
+
+        let hash = start(key.hash(alg)?, prot.as_ref())?;
+
+        Ok(Self {
+            key,
+            alg,
+            prot,
+            head,
+            hash,
+        })
+    }
+}
+
+impl Update for KeySigner<'_> {
+    type Error = Infallible;
+
+    fn update(&mut self, chunk: impl AsRef<[u8]>) -> Result<(), Self::Error> {
+        self.hash.update(chunk)
+    }
+}
+
+impl Signer for KeySigner<'_> {
+    type FinishError = Error;
+
+    fn finish(self, mut rng: impl CryptoRngCore) -> Result<Signature, Self::FinishError> {
+        let hash = self.hash.finish()?;
+        let signature = self.key.sign(self.alg, hash, &mut rng)?;
+
+        Ok(Signature {
+            header: self.head,
+            protected: self.prot,
+            signature: signature.into(),
+        })
+    }
+}
+
+/// The signature verification state of the keys implemented by this crate.
+pub struct KeyVerifier<'a> {
+    key: Public,
+    alg: Signing,
+    hash: Optional<Hash>,
+    sig: &'a [u8],
+}
+
+impl<'a> KeyVerifier<'a> {
+    fn new(key: Public, info: &impl KeyInfo, sig: &'a Signature) -> Result<Self, Error> {
+        let prot = sig.protected.as_ref();
+        let alg = algorithm(prot.map(|x| &**x), sig.header.as_ref())?.ok_or(Error::Malformed)?;
+
+        if !info.is_supported(&alg.into()) {
+            return Err(rejected(key.family(alg)));
+        }
+
+        let hash = start(key.hash(alg)?, prot)?;
+
+        Ok(Self {
+            key,
+            alg,
+            hash,
+            sig: &sig.signature,
+        })
+    }
+}
+
+impl Update for KeyVerifier<'_> {
+    type Error = Infallible;
+
+    fn update(&mut self, chunk: impl AsRef<[u8]>) -> Result<(), Self::Error> {
+        self.hash.update(chunk)
+    }
+}
+
+impl<'a> Verifier<'a> for KeyVerifier<'a> {
+    type FinishError = Error;
+
+    fn finish(self) -> Result<(), Self::FinishError> {
+        let hash = self.hash.finish()?;
+        self.key.verify(self.alg, hash, self.sig)
+    }
+}
+
+impl<'a> SigningKey<'a> for Key {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(self.try_into()?, self, prot, head)
+    }
+}
+
+impl<'a> VerifyingKey<'a, &'a Signature> for Key {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(self.try_into()?, self, val)
+    }
+}
+
+#[cfg(feature = "x509")]
+impl<'a> KeyVerifier<'a> {
+    /// Begins verifying a signature with a key which is not borrowed.
+    pub(super) fn owned(key: &Key, sig: &'a Signature) -> Result<Self, Error> {
+        Self::new(key.try_into()?, key, sig)
+    }
+}
+
+/// Verification with a JWK cross-checks its `alg`, `use` and `key_ops`
+/// parameters, when present, against the algorithm of the signature.
+///
+/// Since the `alg` header parameter is chosen by the creator of the JWS,
+/// [`verify`](VerifyingKey::verify) requires the JWK to pin its algorithm
+/// and fails with [`Error::Policy`] otherwise. Use
+/// [`verify_allowed`](VerifyingKey::verify_allowed) to supply an allow-list.
+impl<'a> VerifyingKey<'a, &'a Signature> for Jwk {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        match self.prm.alg {
+            Some(Algorithm::Signing(alg)) => self.verify_allowed(val, &[alg]),
+            _ => Err(Error::Policy),
+        }
+    }
+
+    fn verify_allowed(
+        &'a self,
+        val: &'a Signature,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError> {
+        permits(&self.prm, allowed(val, algs)?)?;
+
+        let key = Key::try_from(&self.key)?;
+        KeyVerifier::new((&key).try_into()?, &key, val)
+    }
+}
+
+#[cfg(feature = "p256")]
+impl<'a> SigningKey<'a> for p256::SecretKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::P256(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "p256")]
+impl<'a> VerifyingKey<'a, &'a Signature> for p256::PublicKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::P256(*self), self, val)
+    }
+}
+
+#[cfg(feature = "p384")]
+impl<'a> SigningKey<'a> for p384::SecretKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::P384(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "p384")]
+impl<'a> VerifyingKey<'a, &'a Signature> for p384::PublicKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::P384(*self), self, val)
+    }
+}
+
+#[cfg(feature = "p521")]
+impl<'a> SigningKey<'a> for p521::SecretKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::P521(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "p521")]
+impl<'a> VerifyingKey<'a, &'a Signature> for p521::PublicKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::P521(*self), self, val)
+    }
+}
+
+#[cfg(feature = "k256")]
+impl<'a> SigningKey<'a> for k256::SecretKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::P256K(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "k256")]
+impl<'a> VerifyingKey<'a, &'a Signature> for k256::PublicKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::P256K(*self), self, val)
+    }
+}
+
+#[cfg(feature = "rsa")]
+impl<'a> SigningKey<'a> for rsa::RsaPrivateKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::Rsa(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "rsa")]
+impl<'a> VerifyingKey<'a, &'a Signature> for rsa::RsaPublicKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::Rsa(self.clone()), self, val)
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl<'a> SigningKey<'a> for ed25519_dalek::SigningKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::Ed25519(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "ed25519")]
+impl<'a> VerifyingKey<'a, &'a Signature> for ed25519_dalek::VerifyingKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::Ed25519(*self), self, val)
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl<'a> SigningKey<'a> for ed448_goldilocks_plus::SigningKey {
+    type StartError = Error;
+    type Signer = KeySigner<'a>;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        KeySigner::new(Secret::Ed448(self), self, prot, head)
+    }
+}
+
+#[cfg(feature = "ed448")]
+impl<'a> VerifyingKey<'a, &'a Signature> for ed448_goldilocks_plus::VerifyingKey {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        KeyVerifier::new(Public::Ed448(*self), self, val)
+    }
+}
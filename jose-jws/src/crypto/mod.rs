@@ -3,12 +3,76 @@
 
 //! JWS Cryptographic Implementation
 
+mod alg;
+mod crit;
+mod detached;
+mod ecdsa;
+mod eddsa;
+mod error;
+mod general;
+mod key;
+mod oneshot;
+mod policy;
+mod resolve;
+mod rsa;
+mod x509;
+
+#[cfg(any(
+    feature = "p256",
+    feature = "p384",
+    feature = "p521",
+    feature = "k256",
+    feature = "rsa",
+    feature = "hmac",
+    feature = "ed25519",
+    feature = "ed448"
+))]
+pub use key::{KeySigner, KeyVerifier};
+
+pub use alg::allowed;
+pub use crit::critical;
+pub use detached::DetachedVerifier;
+pub use error::Error;
+pub use general::GeneralSigner;
+#[cfg(any(
+    feature = "p256",
+    feature = "p384",
+    feature = "p521",
+    feature = "k256",
+    feature = "rsa",
+    feature = "hmac",
+    feature = "ed25519",
+    feature = "ed448"
+))]
+pub use oneshot::verify_jwks;
+pub use oneshot::{sign, sign_all, verify, verify_detached, Verified};
+pub use policy::{verify_policy, Passed, Policy, Report};
+pub use resolve::{candidates, resolve};
+#[cfg(all(
+    feature = "x509",
+    any(
+        feature = "p256",
+        feature = "p384",
+        feature = "p521",
+        feature = "k256",
+        feature = "rsa",
+        feature = "hmac",
+        feature = "ed25519",
+        feature = "ed448"
+    )
+))]
+pub use x509::TrustAnchors;
+
 use alloc::{vec, vec::Vec};
 
 use jose_b64::stream::Update;
-use rand_core::RngCore;
+use jose_jwa::Signing;
+use jose_jwk::crypto::Restricted;
+use jose_jwk::Operations;
+use rand_core::CryptoRngCore;
 
 use crate::{Flattened, General, Jws, Protected, Signature, Unprotected};
+use alg::Allow;
 
 /// Signature creation state
 pub trait Signer: Update {
@@ -16,7 +80,7 @@ pub trait Signer: Update {
     type FinishError: From<Self::Error>;
 
     /// Finish processing payload and create the signature.
-    fn finish(self, rng: impl 'static + RngCore) -> Result<Signature, Self::FinishError>;
+    fn finish(self, rng: impl CryptoRngCore) -> Result<Signature, Self::FinishError>;
 }
 
 /// A signature creation key
@@ -44,23 +108,26 @@ pub trait Verifier<'a>: Update {
     fn finish(self) -> Result<(), Self::FinishError>;
 }
 
+/// Succeeds when any of the verifiers succeeds.
+///
+/// Otherwise, the errors of all verifiers are combined, in order.
 impl<'a, T: Verifier<'a>> Verifier<'a> for Vec<T>
 where
-    T::FinishError: Default,
+    T::FinishError: From<Vec<T::FinishError>>,
 {
     type FinishError = T::FinishError;
 
     fn finish(self) -> Result<(), Self::FinishError> {
-        let mut last = T::FinishError::default();
+        let mut all = Vec::new();
 
         for x in self {
             match x.finish() {
                 Ok(()) => return Ok(()),
-                Err(e) => last = e,
+                Err(e) => all.push(e),
             }
         }
 
-        Err(last)
+        Err(all.into())
     }
 }
 
@@ -74,12 +141,33 @@ pub trait VerifyingKey<'a, T> {
 
     /// Begin the signature verification process.
     fn verify(&'a self, val: T) -> Result<Self::Verifier, Self::StartError>;
+
+    /// Begin the signature verification process, accepting only the
+    /// algorithms in `algs` (see [`allowed`]).
+    ///
+    /// Keys which carry their own restrictions, like a [`Jwk`] with a pinned
+    /// `alg`, check the algorithm against those in [`verify`](Self::verify);
+    /// this checks it against the caller's list instead.
+    ///
+    /// [`Jwk`]: jose_jwk::Jwk
+    fn verify_allowed(
+        &'a self,
+        val: T,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError>
+    where
+        T: Allow,
+        Self::StartError: From<Error>,
+    {
+        val.allow(algs)?;
+        self.verify(val)
+    }
 }
 
 impl<'a, A, T, V> VerifyingKey<'a, A> for [T]
 where
     T: VerifyingKey<'a, A, Verifier = Vec<V>>,
-    V::FinishError: Default,
+    V::FinishError: From<Vec<V::FinishError>>,
     V: Verifier<'a>,
     V: Update,
     A: Copy,
@@ -96,11 +184,71 @@ where
 
         Ok(all)
     }
+
+    fn verify_allowed(
+        &'a self,
+        val: A,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError>
+    where
+        A: Allow,
+        Self::StartError: From<Error>,
+    {
+        let mut all = Vec::new();
+
+        for key in self {
+            all.extend(key.verify_allowed(val, algs)?);
+        }
+
+        Ok(all)
+    }
+}
+
+/// Signing with a restricted key requires the `sign` operation.
+impl<'a, K: SigningKey<'a>> SigningKey<'a> for Restricted<K>
+where
+    K::StartError: From<jose_jwk::crypto::Error>,
+{
+    type StartError = K::StartError;
+    type Signer = K::Signer;
+
+    fn sign(
+        &'a self,
+        prot: Option<Protected>,
+        head: Option<Unprotected>,
+    ) -> Result<Self::Signer, Self::StartError> {
+        self.get(Operations::Sign)?.sign(prot, head)
+    }
+}
+
+/// Verifying with a restricted key requires the `verify` operation.
+impl<'a, K: VerifyingKey<'a, &'a Signature>> VerifyingKey<'a, &'a Signature> for Restricted<K>
+where
+    K::StartError: From<jose_jwk::crypto::Error>,
+{
+    type StartError = K::StartError;
+    type Verifier = K::Verifier;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        self.get(Operations::Verify)?.verify(val)
+    }
+
+    fn verify_allowed(
+        &'a self,
+        val: &'a Signature,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError>
+    where
+        Self::StartError: From<Error>,
+    {
+        self.get(Operations::Verify)?.verify_allowed(val, algs)
+    }
 }
 
 impl<'a, T: VerifyingKey<'a, &'a Signature>> VerifyingKey<'a, &'a Flattened> for T
 where
-    <T::Verifier as Verifier<'a>>::FinishError: Default,
+    <T::Verifier as Verifier<'a>>::FinishError:
+        From<Vec<<T::Verifier as Verifier<'a>>::FinishError>>,
 {
     type StartError = T::StartError;
     type Verifier = Vec<T::Verifier>;
@@ -108,11 +256,23 @@ where
     fn verify(&'a self, flattened: &'a Flattened) -> Result<Self::Verifier, Self::StartError> {
         Ok(vec![self.verify(&flattened.signature)?])
     }
+
+    fn verify_allowed(
+        &'a self,
+        flattened: &'a Flattened,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError>
+    where
+        Self::StartError: From<Error>,
+    {
+        Ok(vec![self.verify_allowed(&flattened.signature, algs)?])
+    }
 }
 
 impl<'a, T: VerifyingKey<'a, &'a Signature>> VerifyingKey<'a, &'a General> for T
 where
-    <T::Verifier as Verifier<'a>>::FinishError: Default,
+    <T::Verifier as Verifier<'a>>::FinishError:
+        From<Vec<<T::Verifier as Verifier<'a>>::FinishError>>,
 {
     type StartError = T::StartError;
     type Verifier = Vec<T::Verifier>;
@@ -124,6 +284,21 @@ where
             .map(|sig| self.verify(sig))
             .collect()
     }
+
+    fn verify_allowed(
+        &'a self,
+        general: &'a General,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError>
+    where
+        Self::StartError: From<Error>,
+    {
+        general
+            .signatures
+            .iter()
+            .map(|sig| self.verify_allowed(sig, algs))
+            .collect()
+    }
 }
 
 impl<'a, T, V, E> VerifyingKey<'a, &'a Jws> for T
@@ -142,4 +317,18 @@ where
             Jws::Flattened(flattened) => self.verify(flattened),
         }
     }
+
+    fn verify_allowed(
+        &'a self,
+        jws: &'a Jws,
+        algs: &[Signing],
+    ) -> Result<Self::Verifier, Self::StartError>
+    where
+        Self::StartError: From<Error>,
+    {
+        match jws {
+            Jws::General(general) => self.verify_allowed(general, algs),
+            Jws::Flattened(flattened) => self.verify_allowed(flattened, algs),
+        }
+    }
 }
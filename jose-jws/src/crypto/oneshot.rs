@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! One-shot signing and verification on top of the streaming traits.
+
+use alloc::vec::Vec;
+use core::slice;
+
+use jose_b64::stream::Update;
+use jose_jwa::Signing;
+use rand_core::CryptoRngCore;
+
+use super::{allowed, critical, Error, GeneralSigner, Signer, SigningKey, Verifier, VerifyingKey};
+use crate::{Flattened, Jws, Protected, Signature, Unprotected};
+
+/// A successfully verified signature and the payload it covers.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Verified<'a> {
+    /// The verified payload.
+    pub payload: &'a [u8],
+
+    /// The JWS Protected Header of the verified signature.
+    pub protected: Option<&'a Protected>,
+
+    /// The JWS Unprotected Header of the verified signature.
+    pub header: Option<&'a Unprotected>,
+}
+
+impl<'a> Verified<'a> {
+    pub(super) fn new(payload: &'a [u8], sig: &'a Signature) -> Self {
+        Self {
+            payload,
+            protected: sig.protected.as_deref(),
+            header: sig.header.as_ref(),
+        }
+    }
+}
+
+fn signature<'a, K>(
+    key: &'a K,
+    prot: Option<Protected>,
+    head: Option<Unprotected>,
+    payload: &[u8],
+    rng: impl CryptoRngCore,
+) -> Result<Signature, K::StartError>
+where
+    K: SigningKey<'a> + ?Sized,
+    K::StartError: From<<K::Signer as Signer>::FinishError>,
+{
+    let mut signer = key.sign(prot, head)?;
+    signer.update(payload)?;
+    Ok(signer.finish(rng)?)
+}
+
+/// Signs the payload with a single key, creating a flattened JWS.
+pub fn sign<'a, K>(
+    key: &'a K,
+    prot: Option<Protected>,
+    head: Option<Unprotected>,
+    payload: &[u8],
+    rng: impl CryptoRngCore,
+) -> Result<Jws, K::StartError>
+where
+    K: SigningKey<'a> + ?Sized,
+    K::StartError: From<<K::Signer as Signer>::FinishError>,
+{
+    let signature = signature(key, prot, head, payload, rng)?;
+
+    Ok(Jws::Flattened(Flattened {
+        payload: Some(payload.to_vec().into()),
+        signature,
+    }))
+}
+
+/// Signs the payload with each of the keys, creating a general JWS.
+///
+/// At least one key must be provided. See [`GeneralSigner`] for streaming
+/// the payload.
+pub fn sign_all<'a, K, I>(
+    keys: I,
+    payload: &[u8],
+    rng: impl CryptoRngCore,
+) -> Result<Jws, K::StartError>
+where
+    K: SigningKey<'a> + ?Sized + 'a,
+    K::StartError: From<<K::Signer as Signer>::FinishError> + From<Error>,
+    <K::Signer as Signer>::FinishError: From<Error>,
+    I: IntoIterator<Item = (&'a K, Option<Protected>, Option<Unprotected>)>,
+{
+    let mut builder = GeneralSigner::new();
+    for (key, prot, head) in keys {
+        builder = builder.add(key, prot, head)?;
+    }
+
+    Ok(builder.chain(payload)?.finish(rng)?.into())
+}
+
+/// Returns the attached payload and the signatures of a JWS.
+pub(super) fn parts(jws: &Jws) -> (Option<&[u8]>, &[Signature]) {
+    match jws {
+        Jws::General(x) => (x.payload.as_ref().map(|x| &x[..]), &x.signatures),
+        Jws::Flattened(x) => (
+            x.payload.as_ref().map(|x| &x[..]),
+            slice::from_ref(&x.signature),
+        ),
+    }
+}
+
+pub(super) fn check<'a, K>(
+    key: &'a K,
+    sig: &'a Signature,
+    payload: &[u8],
+    algs: &[Signing],
+) -> Result<(), K::StartError>
+where
+    K: VerifyingKey<'a, &'a Signature> + ?Sized,
+    K::StartError: From<<K::Verifier as Verifier<'a>>::FinishError> + From<Error>,
+{
+    let mut verifier = key.verify_allowed(sig, algs)?;
+    verifier.update(payload)?;
+    Ok(verifier.finish()?)
+}
+
+/// Checks the header parameters of a signature before it is verified.
+///
+/// An unsecured signature (RFC 7518 Section 3.6) needs no key: when `algs`
+/// allows it, its signature must be empty and `true` is returned.
+pub(super) fn headers(sig: &Signature, algs: &[Signing], crit: &[&str]) -> Result<bool, Error> {
+    critical(sig, crit)?;
+
+    match allowed(sig, algs)? {
+        Signing::Null if sig.signature.is_empty() => Ok(true),
+        Signing::Null => Err(Error::Signature),
+        _ => Ok(false),
+    }
+}
+
+/// Verifies the signatures with a single key, returning the first success.
+fn first<'a, K>(
+    key: &'a K,
+    signatures: &'a [Signature],
+    payload: &'a [u8],
+    algs: &[Signing],
+    crit: &[&str],
+) -> Result<Verified<'a>, K::StartError>
+where
+    K: VerifyingKey<'a, &'a Signature> + ?Sized,
+    K::StartError: From<<K::Verifier as Verifier<'a>>::FinishError>,
+    K::StartError: From<Error> + From<Vec<K::StartError>>,
+{
+    let mut errors = Vec::with_capacity(signatures.len());
+    for sig in signatures {
+        match headers(sig, algs, crit) {
+            Ok(true) => return Ok(Verified::new(payload, sig)),
+            Ok(false) => (),
+            Err(e) => {
+                errors.push(e.into());
+                continue;
+            }
+        }
+
+        match check(key, sig, payload, algs) {
+            Ok(()) => return Ok(Verified::new(payload, sig)),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Err(errors.into())
+}
+
+/// Verifies a JWS with a single key.
+///
+/// Succeeds when any of the signatures verifies and returns the first such
+/// signature; otherwise the errors of all signatures are combined. A JWS
+/// without an attached payload is rejected. Signatures whose algorithm is not
+/// in `algs` (see [`allowed`]) or with critical header parameters outside of
+/// `crit` (see [`critical`]) are rejected.
+///
+/// Listing [`Signing::Null`] in `algs` accepts unsecured signatures, which
+/// are not verified with the key at all.
+pub fn verify<'a, K>(
+    key: &'a K,
+    jws: &'a Jws,
+    algs: &[Signing],
+    crit: &[&str],
+) -> Result<Verified<'a>, K::StartError>
+where
+    K: VerifyingKey<'a, &'a Signature> + ?Sized,
+    K::StartError: From<<K::Verifier as Verifier<'a>>::FinishError>,
+    K::StartError: From<Error> + From<Vec<K::StartError>>,
+{
+    let (payload, signatures) = parts(jws);
+    let payload = payload.ok_or(Error::Malformed)?;
+    first(key, signatures, payload, algs, crit)
+}
+
+/// Verifies a JWS with a detached payload (RFC 7515 Appendix F).
+///
+/// This behaves like [`verify`], except that the payload is supplied by the
+/// caller. Any attached payload is ignored.
+pub fn verify_detached<'a, K>(
+    key: &'a K,
+    jws: &'a Jws,
+    payload: &'a [u8],
+    algs: &[Signing],
+    crit: &[&str],
+) -> Result<Verified<'a>, K::StartError>
+where
+    K: VerifyingKey<'a, &'a Signature> + ?Sized,
+    K::StartError: From<<K::Verifier as Verifier<'a>>::FinishError>,
+    K::StartError: From<Error> + From<Vec<K::StartError>>,
+{
+    first(key, parts(jws).1, payload, algs, crit)
+}
+
+/// Verifies a JWS with the keys of a JWK Set.
+///
+/// Each signature is verified with the single key which its headers select
+/// (see [`resolve`](super::resolve)). Allowed algorithms and critical header
+/// parameters are handled as in [`verify`].
+#[cfg(any(
+    feature = "p256",
+    feature = "p384",
+    feature = "p521",
+    feature = "k256",
+    feature = "rsa",
+    feature = "hmac",
+    feature = "ed25519",
+    feature = "ed448"
+))]
+pub fn verify_jwks<'a>(
+    jwks: &jose_jwk::JwkSet,
+    jws: &'a Jws,
+    algs: &[Signing],
+    crit: &[&str],
+) -> Result<Verified<'a>, Error> {
+    let (payload, signatures) = parts(jws);
+    let payload = payload.ok_or(Error::Malformed)?;
+
+    let mut errors = Vec::with_capacity(signatures.len());
+    for sig in signatures {
+        match headers(sig, algs, crit) {
+            Ok(true) => return Ok(Verified::new(payload, sig)),
+            Ok(false) => (),
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        }
+
+        match super::resolve(jwks, sig).and_then(|jwk| check(jwk, sig, payload, algs)) {
+            Ok(()) => return Ok(Verified::new(payload, sig)),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Err(errors.into())
+}
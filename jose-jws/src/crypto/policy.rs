@@ -0,0 +1,157 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Policy-driven verification of JWS with several signatures.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use jose_jwa::Signing;
+
+use super::oneshot::{check, headers, parts};
+use super::{Error, Verified, Verifier, VerifyingKey};
+use crate::{Jws, Signature};
+
+/// The signatures which must verify for a JWS to be accepted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Policy {
+    /// At least one signature must verify.
+    Any,
+
+    /// Every signature must verify.
+    All,
+
+    /// Signatures from at least this many of the provided keys must verify.
+    ///
+    /// A threshold of zero is treated as one.
+    Threshold(usize),
+
+    /// For each of these key identifiers, a signature must verify with the
+    /// key which has it.
+    ///
+    /// The identifiers are those provided with the keys. The `kid` header
+    /// parameters are not considered, since a signer may name any key.
+    Kids(Vec<String>),
+}
+
+/// A signature which verified.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Passed<'a> {
+    /// The index of the key which verified the signature.
+    pub key: usize,
+
+    /// The identifier of the key which verified the signature, if any.
+    pub kid: Option<&'a str>,
+
+    /// The verified signature.
+    pub verified: Verified<'a>,
+}
+
+/// The outcome of verifying each signature of a JWS.
+#[derive(Clone, Debug)]
+pub struct Report<'a, E> {
+    /// The result for each signature, in the order of the JWS.
+    pub results: Vec<Result<Passed<'a>, E>>,
+}
+
+impl<'a, E> Report<'a, E> {
+    /// Returns the indices and details of the signatures which verified.
+    pub fn passed(&self) -> impl Iterator<Item = (usize, &Passed<'a>)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, x)| x.as_ref().ok().map(|x| (i, x)))
+    }
+
+    /// Returns the indices and errors of the signatures which failed.
+    pub fn failed(&self) -> impl Iterator<Item = (usize, &E)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, x)| x.as_ref().err().map(|x| (i, x)))
+    }
+
+    /// Checks whether the results satisfy the policy.
+    pub fn satisfies(&self, policy: &Policy) -> bool {
+        match policy {
+            Policy::Any => self.passed().next().is_some(),
+
+            Policy::All => !self.results.is_empty() && self.failed().next().is_none(),
+
+            Policy::Threshold(k) => {
+                let mut keys: Vec<usize> = self.passed().map(|(_, x)| x.key).collect();
+                keys.sort_unstable();
+                keys.dedup();
+                keys.len() >= (*k).max(1)
+            }
+
+            Policy::Kids(kids) => kids
+                .iter()
+                .all(|kid| self.passed().any(|(_, x)| x.kid == Some(kid))),
+        }
+    }
+}
+
+/// Verifies each signature of a JWS against the keys and applies a policy.
+///
+/// Each key is provided with its identifier, if any, which [`Policy::Kids`]
+/// requires. Every signature is tried with every key, in order, until one
+/// verifies; otherwise the errors of all keys are combined, in order.
+/// Allowed algorithms and critical header parameters are handled as in
+/// [`verify`](super::verify), except that unsecured signatures fail with
+/// [`Error::Policy`] since no key verifies them. The report is returned in
+/// either case; it is an error when the policy is not satisfied. A JWS
+/// without an attached payload fails every signature.
+pub fn verify_policy<'a, K>(
+    keys: &'a [(Option<&'a str>, K)],
+    jws: &'a Jws,
+    policy: &Policy,
+    algs: &[Signing],
+    crit: &[&str],
+) -> Result<Report<'a, K::StartError>, Report<'a, K::StartError>>
+where
+    K: VerifyingKey<'a, &'a Signature>,
+    K::StartError: From<<K::Verifier as Verifier<'a>>::FinishError>,
+    K::StartError: From<Error> + From<Vec<K::StartError>>,
+{
+    let (payload, signatures) = parts(jws);
+
+    let results = signatures
+        .iter()
+        .map(|sig| {
+            let payload = payload.ok_or(Error::Malformed)?;
+            if headers(sig, algs, crit)? {
+                return Err(Error::Policy.into());
+            }
+
+            if keys.is_empty() {
+                return Err(Error::NoKey.into());
+            }
+
+            let mut errors = Vec::with_capacity(keys.len());
+            for (i, (kid, key)) in keys.iter().enumerate() {
+                match check(key, sig, payload, algs) {
+                    Ok(()) => {
+                        return Ok(Passed {
+                            key: i,
+                            kid: *kid,
+                            verified: Verified::new(payload, sig),
+                        })
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+
+            Err(errors.into())
+        })
+        .collect();
+
+    let report = Report { results };
+    if report.satisfies(policy) {
+        Ok(report)
+    } else {
+        Err(report)
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Selection of verification keys from a JWK Set.
+
+use alloc::vec::Vec;
+
+use jose_jwa::Signing;
+use jose_jwk::crypto::KeyInfo;
+use jose_jwk::{Class, Jwk, JwkSet, Operations, Parameters};
+use jose_jwk::{Thumbprint, ThumbprintHash};
+
+use super::alg::algorithm;
+use super::Error;
+use crate::{Signature, Unprotected};
+
+/// Checks that the JWK parameters permit verifying with the algorithm.
+pub(super) fn permits(prm: &Parameters, alg: Signing) -> Result<(), Error> {
+    if prm.alg.as_ref().map_or(false, |x| *x != alg.into()) {
+        return Err(Error::AlgMismatch);
+    }
+
+    if prm.cls.map_or(false, |x| x != Class::Signing)
+        || prm
+            .ops
+            .as_ref()
+            .map_or(false, |x| !x.contains(&Operations::Verify))
+    {
+        return Err(Error::Operation);
+    }
+
+    Ok(())
+}
+
+/// Finds a header parameter in either of the (disjoint) headers.
+fn find<'a, T: ?Sized>(
+    heads: &[Option<&'a Unprotected>; 2],
+    f: impl Fn(&'a Unprotected) -> Option<&'a T>,
+) -> Option<&'a T> {
+    heads.iter().flatten().find_map(|x| f(x))
+}
+
+/// The key identification hints of a signature's headers.
+struct Hints<'a> {
+    alg: Option<Signing>,
+    kid: Option<&'a str>,
+    x5t: Thumbprint,
+    jwk: Option<Vec<u8>>,
+}
+
+impl<'a> Hints<'a> {
+    fn new(sig: &'a Signature) -> Result<Self, Error> {
+        let prot = sig.protected.as_deref();
+        let alg = algorithm(prot, sig.header.as_ref())?;
+
+        let heads = [prot.map(|x| &x.oth), sig.header.as_ref()];
+
+        Ok(Self {
+            alg,
+            kid: find(&heads, |x| x.kid.as_deref()),
+            x5t: Thumbprint {
+                s1: find(&heads, |x| x.x5t.s1.as_ref()).cloned(),
+                s256: find(&heads, |x| x.x5t.s256.as_ref()).cloned(),
+            },
+            jwk: find(&heads, |x| x.jwk.as_ref()).map(|x| x.thumbprint(ThumbprintHash::Sha256)),
+        })
+    }
+
+    /// Identifiers in the headers must match the key, and the key's type and
+    /// parameters must permit verifying with the algorithm.
+    fn matches(&self, jwk: &Jwk) -> bool {
+        let prm = &jwk.prm;
+
+        (self.kid.is_none() || prm.kid.as_deref() == self.kid)
+            && (self.x5t.s1.is_none() || prm.x5t.s1 == self.x5t.s1)
+            && (self.x5t.s256.is_none() || prm.x5t.s256 == self.x5t.s256)
+            && self
+                .jwk
+                .as_ref()
+                .map_or(true, |x| *x == jwk.thumbprint(ThumbprintHash::Sha256))
+            && self.alg.map_or(true, |alg| {
+                jwk.key.is_supported(&alg.into()) && permits(prm, alg).is_ok()
+            })
+    }
+}
+
+/// Selects the keys of a JWK Set which may verify a signature.
+///
+/// A key is selected when it has the `kid`, `x5t` and `x5t#S256` named by
+/// the signature's headers, and when its type and its `alg`, `use` and
+/// `key_ops` parameters permit verifying with the signature's algorithm. An embedded
+/// `jwk` header parameter only selects the key of the set with the same
+/// RFC 7638 thumbprint; it is never trusted on its own.
+pub fn candidates<'a>(jwks: &'a JwkSet, sig: &Signature) -> Result<Vec<&'a Jwk>, Error> {
+    let hints = Hints::new(sig)?;
+    Ok(jwks.keys.iter().filter(|x| hints.matches(x)).collect())
+}
+
+/// Resolves the single key of a JWK Set which may verify a signature.
+///
+/// Keys are selected as in [`candidates`]. Fails with [`Error::NoKey`] when
+/// none is selected and with [`Error::Ambiguous`] when several are.
+pub fn resolve<'a>(jwks: &'a JwkSet, sig: &Signature) -> Result<&'a Jwk, Error> {
+    match candidates(jwks, sig)?[..] {
+        [] => Err(Error::NoKey),
+        [jwk] => Ok(jwk),
+        _ => Err(Error::Ambiguous),
+    }
+}
@@ -0,0 +1,58 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! RSA signatures as described in RFC 7518 Sections 3.3 and 3.5
+
+#![cfg(feature = "rsa")]
+
+use alloc::vec::Vec;
+
+use jose_jwa::{Signing, Signing::*};
+use rand_core::CryptoRngCore;
+use rsa::{Pkcs1v15Sign, Pss, RsaPrivateKey, RsaPublicKey};
+use sha2::{Sha256, Sha384, Sha512};
+
+use super::Error;
+
+/// Signs a digest of the JWS Signing Input.
+///
+/// The random number generator is used for PSS salts and for blinding the
+/// private key operation.
+pub(super) fn sign(
+    key: &RsaPrivateKey,
+    alg: Signing,
+    digest: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Result<Vec<u8>, Error> {
+    let sig = match alg {
+        Rs256 => key.sign_with_rng(rng, Pkcs1v15Sign::new::<Sha256>(), digest),
+        Rs384 => key.sign_with_rng(rng, Pkcs1v15Sign::new::<Sha384>(), digest),
+        Rs512 => key.sign_with_rng(rng, Pkcs1v15Sign::new::<Sha512>(), digest),
+        Ps256 => key.sign_with_rng(rng, Pss::new_blinded::<Sha256>(), digest),
+        Ps384 => key.sign_with_rng(rng, Pss::new_blinded::<Sha384>(), digest),
+        Ps512 => key.sign_with_rng(rng, Pss::new_blinded::<Sha512>(), digest),
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    sig.map_err(|_| Error::Malformed)
+}
+
+/// Verifies a signature over a digest of the JWS Signing Input.
+pub(super) fn verify(
+    key: &RsaPublicKey,
+    alg: Signing,
+    digest: &[u8],
+    sig: &[u8],
+) -> Result<(), Error> {
+    let res = match alg {
+        Rs256 => key.verify(Pkcs1v15Sign::new::<Sha256>(), digest, sig),
+        Rs384 => key.verify(Pkcs1v15Sign::new::<Sha384>(), digest, sig),
+        Rs512 => key.verify(Pkcs1v15Sign::new::<Sha512>(), digest, sig),
+        Ps256 => key.verify(Pss::new::<Sha256>(), digest, sig),
+        Ps384 => key.verify(Pss::new::<Sha384>(), digest, sig),
+        Ps512 => key.verify(Pss::new::<Sha512>(), digest, sig),
+        _ => return Err(Error::AlgMismatch),
+    };
+
+    res.map_err(|_| Error::Signature)
+}
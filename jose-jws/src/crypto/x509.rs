@@ -0,0 +1,266 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Validation of `x5c` certificate chains as described in RFC 7515 Section 4.1.6.
+
+#![cfg(all(
+    feature = "x509",
+    any(
+        feature = "p256",
+        feature = "p384",
+        feature = "p521",
+        feature = "k256",
+        feature = "rsa",
+        feature = "hmac",
+        feature = "ed25519",
+        feature = "ed448"
+    )
+))]
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use jose_jwa::Signing;
+use jose_jwk::pkcs8::{spki, DecodePublicKey, EncodePublicKey};
+use jose_jwk::{Jwk, Key};
+use x509_cert::der::asn1::UintRef;
+use x509_cert::der::oid::{AssociatedOid, ObjectIdentifier};
+use x509_cert::der::{Decode, Encode};
+use x509_cert::ext::pkix::{BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAltName};
+use x509_cert::Certificate;
+
+use super::key::{verify_message, KeyVerifier};
+use super::{Error, VerifyingKey};
+use crate::Signature;
+
+const ECDSA_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+const ECDSA_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+const ECDSA_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.4");
+const RSA_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const RSA_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12");
+const RSA_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.13");
+const ED25519: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+const ED448: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.113");
+
+/// The extensions which are understood, and so may be critical.
+const KNOWN: [ObjectIdentifier; 4] = [
+    BasicConstraints::OID,
+    KeyUsage::OID,
+    ExtendedKeyUsage::OID,
+    SubjectAltName::OID,
+];
+
+/// Reports any failure other than a missing backend as a rejected chain.
+fn rejected(err: Error) -> Error {
+    match err {
+        Error::Unsupported => err,
+        _ => Error::Certificate,
+    }
+}
+
+/// The JWS algorithm equivalent to a certificate signature algorithm.
+fn signing(oid: ObjectIdentifier) -> Result<Signing, Error> {
+    Ok(match oid {
+        ECDSA_SHA256 => Signing::Es256,
+        ECDSA_SHA384 => Signing::Es384,
+        ECDSA_SHA512 => Signing::Es512,
+        RSA_SHA256 => Signing::Rs256,
+        RSA_SHA384 => Signing::Rs384,
+        RSA_SHA512 => Signing::Rs512,
+        ED25519 | ED448 => Signing::EdDsa,
+        _ => return Err(Error::Unsupported),
+    })
+}
+
+/// Converts a DER-encoded ECDSA signature to the fixed-size `R || S` form.
+fn fixed(sig: &[u8], len: usize) -> Result<Vec<u8>, Error> {
+    let ints = Vec::<UintRef<'_>>::from_der(sig).map_err(|_| Error::Certificate)?;
+    if ints.len() != 2 {
+        return Err(Error::Certificate);
+    }
+
+    let mut out = Vec::with_capacity(len * 2);
+    for int in ints {
+        let int = int.as_bytes();
+        if int.len() > len {
+            return Err(Error::Certificate);
+        }
+
+        out.resize(out.len() + len - int.len(), 0);
+        out.extend_from_slice(int);
+    }
+
+    Ok(out)
+}
+
+/// Decodes the subject public key of a certificate.
+fn public(cert: &Certificate) -> Result<Key, Error> {
+    let spki = cert.tbs_certificate.subject_public_key_info.to_der();
+    let spki = spki.map_err(|_| Error::Certificate)?;
+
+    Key::from_public_key_der(&spki).map_err(|e| match e {
+        spki::Error::OidUnknown { .. } => Error::Unsupported,
+        _ => Error::Certificate,
+    })
+}
+
+/// Checks that the certificate was signed by the subject of the issuer.
+fn issued(cert: &Certificate, issuer: &Certificate) -> Result<(), Error> {
+    let tbs = &cert.tbs_certificate;
+    if tbs.issuer != issuer.tbs_certificate.subject || tbs.signature != cert.signature_algorithm {
+        return Err(Error::Certificate);
+    }
+
+    let alg = signing(cert.signature_algorithm.oid)?;
+    let sig = cert.signature.as_bytes().ok_or(Error::Certificate)?;
+    let sig = match alg {
+        Signing::Es256 => fixed(sig, 32)?,
+        Signing::Es384 => fixed(sig, 48)?,
+        Signing::Es512 => fixed(sig, 66)?,
+        _ => sig.to_vec(),
+    };
+
+    let key = jose_jwk::crypto::Key::try_from(&public(issuer)?);
+    let key = key.map_err(|e| rejected(e.into()))?;
+    let msg = tbs.to_der().map_err(|_| Error::Certificate)?;
+    verify_message(&key, alg, &msg, &sig).map_err(rejected)
+}
+
+/// Certificates which are trusted to issue `x5c` certificate chains.
+///
+/// Chains are validated offline, at the time given by the caller: each
+/// certificate must be valid at that time and must be issued by the next one,
+/// which must be a CA permitted to sign certificates. The last certificate
+/// must either be one of the roots or be issued by one. Critical extensions
+/// other than basic constraints, key usage, extended key usage and subject
+/// alternative names are rejected. Revocation is not checked.
+///
+/// Certificate signatures use the algorithms of JWS: ECDSA on P-256, P-384
+/// and P-521 with the matching hash, RSASSA-PKCS1-v1_5 and EdDSA. Failures
+/// are reported as [`Error::Certificate`], except for algorithms whose
+/// backend is not enabled, which are reported as [`Error::Unsupported`].
+#[derive(Clone, Debug)]
+pub struct TrustAnchors {
+    roots: Vec<Certificate>,
+    time: Duration,
+}
+
+impl TrustAnchors {
+    /// Creates the trust anchors, with the time since the Unix epoch at which
+    /// certificates must be valid.
+    pub fn new(roots: Vec<Certificate>, time: Duration) -> Self {
+        Self { roots, time }
+    }
+
+    /// Validates an `x5c` certificate chain, returning its certificates.
+    fn chain<T: AsRef<[u8]>>(&self, x5c: &[T]) -> Result<Vec<Certificate>, Error> {
+        let chain = x5c
+            .iter()
+            .map(|x| Certificate::from_der(x.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::Certificate)?;
+
+        let last = chain.last().ok_or(Error::Certificate)?;
+
+        for (i, cert) in chain.iter().enumerate() {
+            let tbs = &cert.tbs_certificate;
+            let validity = &tbs.validity;
+            if self.time < validity.not_before.to_unix_duration()
+                || self.time > validity.not_after.to_unix_duration()
+            {
+                return Err(Error::Certificate);
+            }
+
+            let exts = tbs.extensions.as_deref().unwrap_or_default();
+            if exts
+                .iter()
+                .any(|x| x.critical && !KNOWN.contains(&x.extn_id))
+            {
+                return Err(Error::Certificate);
+            }
+
+            // Certificates other than the leaf issue the one before them.
+            if i > 0 {
+                let bc = tbs
+                    .get::<BasicConstraints>()
+                    .map_err(|_| Error::Certificate)?;
+                match bc {
+                    Some((_, bc)) if bc.ca => {
+                        if bc
+                            .path_len_constraint
+                            .map_or(false, |x| usize::from(x) < i - 1)
+                        {
+                            return Err(Error::Certificate);
+                        }
+                    }
+                    _ => return Err(Error::Certificate),
+                }
+
+                let ku = tbs.get::<KeyUsage>().map_err(|_| Error::Certificate)?;
+                if ku.map_or(false, |(_, ku)| !ku.key_cert_sign()) {
+                    return Err(Error::Certificate);
+                }
+
+                issued(&chain[i - 1], cert)?;
+            }
+        }
+
+        if !self.roots.contains(last) && !self.roots.iter().any(|x| issued(last, x).is_ok()) {
+            return Err(Error::Certificate);
+        }
+
+        Ok(chain)
+    }
+
+    /// Validates an `x5c` certificate chain, returning the public key of its
+    /// first (leaf) certificate.
+    pub fn validate<T: AsRef<[u8]>>(&self, x5c: &[T]) -> Result<Key, Error> {
+        public(&self.chain(x5c)?[0])
+    }
+
+    /// Binds a JWK to its `x5c` parameter, as required by RFC 7517 Section
+    /// 4.7: the chain must be valid and the key of its first certificate
+    /// must be the key of the JWK.
+    pub fn bind(&self, jwk: &Jwk) -> Result<(), Error> {
+        let x5c = jwk.prm.x5c.as_ref().ok_or(Error::Certificate)?;
+        let leaf = self.validate(x5c)?.to_public_key_der();
+        let key = jwk.key.to_public_key_der();
+
+        match (leaf, key) {
+            (Ok(leaf), Ok(key)) if leaf == key => Ok(()),
+            _ => Err(Error::Certificate),
+        }
+    }
+}
+
+/// Verification with the key of the `x5c` certificate chain in the
+/// signature's headers, once the chain is validated.
+///
+/// The leaf certificate must permit digital signatures when it has a key
+/// usage extension. A signature without an `x5c` header parameter fails with
+/// [`Error::NoKey`].
+impl<'a> VerifyingKey<'a, &'a Signature> for TrustAnchors {
+    type StartError = Error;
+    type Verifier = KeyVerifier<'a>;
+
+    fn verify(&'a self, val: &'a Signature) -> Result<Self::Verifier, Self::StartError> {
+        let x5c = val
+            .protected
+            .as_deref()
+            .and_then(|x| x.oth.x5c.as_ref())
+            .or_else(|| val.header.as_ref().and_then(|x| x.x5c.as_ref()))
+            .ok_or(Error::NoKey)?;
+
+        let leaf = &self.chain(x5c)?[0];
+        let ku = leaf.tbs_certificate.get::<KeyUsage>();
+        if ku
+            .map_err(|_| Error::Certificate)?
+            .map_or(false, |(_, ku)| !ku.digital_signature())
+        {
+            return Err(Error::Certificate);
+        }
+
+        let key = jose_jwk::crypto::Key::try_from(&public(leaf)?)?;
+        KeyVerifier::owned(&key, val)
+    }
+}
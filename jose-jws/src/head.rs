@@ -8,7 +8,7 @@ use jose_b64::base64ct::Base64;
 use jose_b64::serde::Bytes;
 use jose_jwa::Signing;
 use jose_jwk::{Jwk, Thumbprint};
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 
 #[inline]
 fn b64_default() -> bool {
@@ -16,14 +16,14 @@ fn b64_default() -> bool {
 }
 
 #[inline]
-fn b64_serialize(value: &bool) -> bool {
-    !value
+fn b64_is_default(value: &bool) -> bool {
+    *value
 }
 
 /// The JWS Protected Header
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Protected {
-    /// RFC 7517 Section 4.1.11
+    /// RFC 7515 Section 4.1.11
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub crit: Option<Vec<String>>,
 
@@ -32,7 +32,7 @@ pub struct Protected {
     pub nonce: Option<Bytes>,
 
     /// RFC 7797 Section 3
-    #[serde(skip_serializing_if = "b64_serialize", default = "b64_default")]
+    #[serde(skip_serializing_if = "b64_is_default", default = "b64_default")]
     pub b64: bool,
 
     /// Other values that may appear in the protected header.
@@ -52,7 +52,11 @@ impl Default for Protected {
 }
 
 /// The JWS Unprotected Header
+///
+/// Deserialization fails when `x5t` or `x5t#S256` is not the thumbprint of
+/// the first certificate of `x5c`.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Unprotected {
     /// RFC 7515 Section 4.1.1
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -81,4 +85,38 @@ pub struct Unprotected {
     /// RFC 7515 Section 4.1.10
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub cty: Option<String>,
+
+    /// RFC 7515 Section 4.1.11
+    ///
+    /// This must only appear in the JWS Protected Header (see
+    /// [`Protected::crit`]). It is parsed here so that it can be rejected.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub crit: Option<Vec<String>>,
+}
+
+impl Unprotected {
+    /// Sets `x5t` and `x5t#S256` to the thumbprints of the first certificate
+    /// of `x5c`, if any.
+    pub fn set_x5t(&mut self) {
+        if let Some(x5c) = self.x5c.as_deref() {
+            self.x5t = Thumbprint::from_x5c(x5c);
+        }
+    }
+}
+
+// See the implementations for `jose_jwk::Parameters`.
+impl Serialize for Unprotected {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Unprotected {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let head = Self::deserialize(deserializer)?;
+        match head.x5t.matches(head.x5c.as_deref().unwrap_or_default()) {
+            true => Ok(head),
+            false => Err(D::Error::custom("x5t does not match x5c")),
+        }
+    }
 }
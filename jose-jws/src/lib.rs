@@ -21,12 +21,21 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod crypto;
 
 mod compact;
 mod head;
+mod payload;
+mod strict;
 
 pub use head::{Protected, Unprotected};
+pub use strict::{CompactError, Limits, Segment};
+
+#[cfg(feature = "x509")]
+pub use x509_cert;
 
 use alloc::{vec, vec::Vec};
 
@@ -76,9 +85,12 @@ impl From<Flattened> for Jws {
 ///       "signature":"<signature N contents>"}]
 /// }
 /// ```
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug)]
 pub struct General {
     /// The payload of the signature.
+    ///
+    /// This is the raw payload. It is serialized base64url encoded unless the
+    /// `b64` header is `false` (RFC 7797).
     pub payload: Option<Bytes>,
 
     /// The signatures over the payload.
@@ -107,13 +119,15 @@ impl From<Flattened> for General {
 ///     "signature":"<signature contents>"
 /// }
 /// ```
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug)]
 pub struct Flattened {
     /// The payload of the signature.
+    ///
+    /// This is the raw payload. It is serialized base64url encoded unless the
+    /// `b64` header is `false` (RFC 7797).
     pub payload: Option<Bytes>,
 
     /// The signature over the payload.
-    #[serde(flatten)]
     pub signature: Signature,
 }
 
@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Payload encoding for the JSON serializations, including the unencoded
+//! payload option described in RFC 7797.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use jose_b64::serde::Bytes;
+use serde::{de::Error as _, ser::Error as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Flattened, General, Signature};
+
+impl Signature {
+    /// Whether the payload is base64url encoded (RFC 7797 Section 3).
+    pub(crate) fn b64(&self) -> bool {
+        self.protected.as_ref().map_or(true, |x| x.b64)
+    }
+}
+
+/// Finds the payload encoding shared by all signatures.
+///
+/// RFC 7797 Section 3 requires the `b64` value to be the same for all of
+/// them.
+fn b64(signatures: &[Signature]) -> Option<bool> {
+    let b64 = signatures.first().map_or(true, Signature::b64);
+    signatures.iter().all(|x| x.b64() == b64).then_some(b64)
+}
+
+/// A payload, serialized either base64url encoded or as-is.
+struct Payload<'a>(&'a [u8], bool);
+
+impl Serialize for Payload<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.1 {
+            true => Base64UrlUnpadded::encode_string(self.0).serialize(serializer),
+            false => core::str::from_utf8(self.0)
+                .map_err(|_| S::Error::custom("unencoded payload is not UTF-8"))?
+                .serialize(serializer),
+        }
+    }
+}
+
+fn decode<E: serde::de::Error>(payload: Option<String>, b64: bool) -> Result<Option<Bytes>, E> {
+    match payload {
+        Some(x) if b64 => Ok(Some(x.parse().map_err(|_| E::custom("invalid base64"))?)),
+        Some(x) => Ok(Some(Vec::from(x).into())),
+        None => Ok(None),
+    }
+}
+
+impl Serialize for General {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            payload: Option<Payload<'a>>,
+            signatures: &'a [Signature],
+        }
+
+        let b64 = b64(&self.signatures).ok_or_else(|| S::Error::custom("mixed b64 values"))?;
+
+        Repr {
+            payload: self.payload.as_ref().map(|x| Payload(x, b64)),
+            signatures: &self.signatures,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for General {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Repr {
+            payload: Option<String>,
+            signatures: Vec<Signature>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let b64 = b64(&repr.signatures).ok_or_else(|| D::Error::custom("mixed b64 values"))?;
+
+        Ok(Self {
+            payload: decode(repr.payload, b64)?,
+            signatures: repr.signatures,
+        })
+    }
+}
+
+impl Serialize for Flattened {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            payload: Option<Payload<'a>>,
+
+            #[serde(flatten)]
+            signature: &'a Signature,
+        }
+
+        let b64 = self.signature.b64();
+
+        Repr {
+            payload: self.payload.as_ref().map(|x| Payload(x, b64)),
+            signature: &self.signature,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Flattened {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Repr {
+            payload: Option<String>,
+
+            #[serde(flatten)]
+            signature: Signature,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+        let b64 = repr.signature.b64();
+
+        Ok(Self {
+            payload: decode(repr.payload, b64)?,
+            signature: repr.signature,
+        })
+    }
+}
@@ -0,0 +1,235 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Strict parsing of the compact serialization.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+use jose_b64::serde::{Bytes, Json};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::{Flattened, Protected, Signature};
+
+/// Size limits for strict compact parsing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum length of the whole token.
+    pub token: usize,
+
+    /// The maximum length of the encoded protected header.
+    pub header: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            token: 64 * 1024,
+            header: 8 * 1024,
+        }
+    }
+}
+
+/// A segment of the compact serialization.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum Segment {
+    Protected,
+    Payload,
+    Signature,
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Protected => f.write_str("protected header"),
+            Self::Payload => f.write_str("payload"),
+            Self::Signature => f.write_str("signature"),
+        }
+    }
+}
+
+/// An error which occurred during strict compact parsing.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CompactError {
+    /// The token is longer than [`Limits::token`].
+    TokenTooLarge,
+
+    /// The protected header is longer than [`Limits::header`].
+    HeaderTooLarge,
+
+    /// The token does not have exactly three segments.
+    Segments,
+
+    /// The segment is not canonical unpadded base64url.
+    Base64(Segment),
+
+    /// The protected header repeats a JSON member.
+    Duplicate(String),
+
+    /// The protected header is not a valid JWS Protected Header.
+    Header(serde_json::Error),
+
+    /// The unencoded payload is not UTF-8 or contains a period.
+    Unencoded,
+}
+
+impl fmt::Display for CompactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TokenTooLarge => f.write_str("token is too large"),
+            Self::HeaderTooLarge => f.write_str("protected header is too large"),
+            Self::Segments => f.write_str("token does not have three segments"),
+            Self::Base64(seg) => write!(f, "{seg} is not canonical base64url"),
+            Self::Duplicate(name) => write!(f, "protected header repeats member {name:?}"),
+            Self::Header(e) => write!(f, "protected header is invalid: {e}"),
+            Self::Unencoded => f.write_str("unencoded payload cannot be attached"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CompactError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Header(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Scans a JSON value, recording the first repeated member name of any
+/// object at any depth.
+struct Unique<'a>(&'a mut Option<String>);
+
+impl<'de> DeserializeSeed<'de> for Unique<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Unique<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut names = BTreeSet::new();
+        while let Some(name) = map.next_key::<String>()? {
+            if names.contains(&name) {
+                *self.0 = Some(name);
+                return Err(de::Error::custom("duplicate member"));
+            }
+
+            map.next_value_seed(Unique(self.0))?;
+            names.insert(name);
+        }
+
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq.next_element_seed(Unique(self.0))?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+/// Checks that the protected header does not repeat any member.
+fn unique(json: &[u8]) -> Result<(), CompactError> {
+    let mut name = None;
+    let mut de = serde_json::Deserializer::from_slice(json);
+
+    let res = Unique(&mut name)
+        .deserialize(&mut de)
+        .and_then(|()| de.end());
+
+    match (res, name) {
+        (Ok(()), ..) => Ok(()),
+        (Err(..), Some(name)) => Err(CompactError::Duplicate(name)),
+        (Err(e), None) => Err(CompactError::Header(e)),
+    }
+}
+
+fn decode(segment: &str, seg: Segment) -> Result<Vec<u8>, CompactError> {
+    Base64UrlUnpadded::decode_vec(segment).map_err(|_| CompactError::Base64(seg))
+}
+
+impl Flattened {
+    /// Strictly parses the compact serialization.
+    ///
+    /// In addition to what [`FromStr`](core::str::FromStr) checks, this
+    /// enforces the size limits, requires canonical base64url (no padding
+    /// and no non-zero trailing bits) and rejects protected headers which
+    /// repeat a JSON member. The compact serialization has no unprotected
+    /// header, so the result never has one.
+    pub fn from_compact(s: &str, limits: &Limits) -> Result<Self, CompactError> {
+        if s.len() > limits.token {
+            return Err(CompactError::TokenTooLarge);
+        }
+
+        let mut iter = s.split('.');
+        let (prot, payl, sign) = match (iter.next(), iter.next(), iter.next(), iter.next()) {
+            (Some(prot), Some(payl), Some(sign), None) => (prot, payl, sign),
+            _ => return Err(CompactError::Segments),
+        };
+
+        if prot.len() > limits.header {
+            return Err(CompactError::HeaderTooLarge);
+        }
+
+        let header = decode(prot, Segment::Protected)?;
+        unique(&header)?;
+
+        let protected: Json<Protected> =
+            Json::try_from(Bytes::<Box<[u8]>>::from(header)).map_err(CompactError::Header)?;
+
+        let payload = match payl {
+            "" => None,
+            _ if !protected.b64 => Some(payl.as_bytes().to_vec().into()),
+            _ => Some(decode(payl, Segment::Payload)?.into()),
+        };
+
+        Ok(Self {
+            payload,
+            signature: Signature {
+                protected: Some(protected),
+                header: None,
+                signature: decode(sign, Segment::Signature)?.into(),
+            },
+        })
+    }
+}
@@ -0,0 +1,1487 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[cfg(all(test, any(feature = "hmac", feature = "p256", feature = "p521")))]
+mod rfc7515 {
+    use jose_b64::stream::Update;
+    use jose_jwk::{crypto, Jwk};
+    use jose_jws::crypto::{Error, Verifier, VerifyingKey};
+    use jose_jws::Flattened;
+
+    fn verify(jwk: serde_json::Value, jws: &str) -> Result<(), Error> {
+        let jwk: Jwk = serde_json::from_value(jwk).unwrap();
+        let key = crypto::Key::try_from(&jwk.key).unwrap();
+        let jws: Flattened = jws.parse().unwrap();
+
+        let mut verifier = key.verify(&jws)?;
+        verifier.update(jws.payload.as_ref().unwrap())?;
+        verifier.finish()
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.1
+    #[cfg(feature = "hmac")]
+    #[test]
+    fn a1() {
+        let jwk = serde_json::json!({
+            "kty": "oct",
+            "k": "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"
+        });
+
+        let jws = "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9\
+            .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+            .dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+        verify(jwk, jws).unwrap();
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.3
+    #[cfg(feature = "p256")]
+    #[test]
+    fn a3() {
+        let jwk = serde_json::json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+            "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0"
+        });
+
+        let jws = "eyJhbGciOiJFUzI1NiJ9\
+            .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+            .DtEhU3ljbEg8L38VWAfUAqOyKAM6-Xx-F4GawxaepmXFCgfTjDxw5djxLa8ISlSApmWQxfKTUJqPP3-Kg6NU1Q";
+
+        verify(jwk, jws).unwrap();
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.4
+    #[cfg(feature = "p521")]
+    #[test]
+    fn a4() {
+        let jwk = serde_json::json!({
+            "kty": "EC",
+            "crv": "P-521",
+            "x": "AekpBQ8ST8a8VcfVOTNl353vSrDCLLJXmPk06wTjxrrjcBpXp5EOnYG_NjFZ6OvLFV1jSfS9tsz4qUxcWceqwQGk",
+            "y": "ADSmRA43Z1DSNx_RvcLI87cdL07l6jQyyBXMoxVg_l2Th-x3S1WDhjDly79ajL4Kkd0AZMaZmh9ubmf63e3kyMj2",
+            "d": "AY5pb7A0UFiB3RELSD64fTLOSV_jazdF7fLYyuTw8lOfRhWg6Y6rUrPAxerEzgdRhajnu0ferB0d53vM9mE15j2C"
+        });
+
+        let jws = "eyJhbGciOiJFUzUxMiJ9\
+            .UGF5bG9hZA\
+            .AdwMgeerwtHoh-l192l60hp9wAHZFVJbLfD_UxMi70cwnZOYaRI1bKPWROc-mZZqwqT2SI-KGDKB34XO0aw_7Xdt\
+            AG8GaSwFKdCAPZgoXD2YBJZCPEX3xKpRwcdOO8KpEHwJjyqOgzDO7iKvU8vcnwNrmxYbSW9ERBXukOXolLzeO_Jn";
+
+        verify(jwk, jws).unwrap();
+    }
+}
+
+#[cfg(all(
+    test,
+    any(feature = "p256", feature = "p384", feature = "p521", feature = "k256")
+))]
+mod ecdsa {
+    use jose_b64::stream::Update;
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::Key;
+    use jose_jws::crypto::{Error, Signer, SigningKey, Verifier, VerifyingKey};
+    use jose_jws::Flattened;
+    use rand_core::OsRng;
+
+    fn roundtrip(key: Key, alg: Signing, len: usize) {
+        let mut signer = key.sign(None, None).unwrap();
+        signer.update("Hello world!").unwrap();
+        let signature = signer.finish(OsRng).unwrap();
+
+        let prot = signature.protected.as_ref().unwrap();
+        assert_eq!(prot.oth.alg, Some(alg));
+        assert_eq!(signature.signature.len(), len);
+
+        let jws = Flattened {
+            payload: Some(b"Hello world!".to_vec().into()),
+            signature,
+        };
+        let jws: Flattened = jws.to_string().parse().unwrap();
+
+        let mut verifier = key.verify(&jws).unwrap();
+        verifier.update("Hello world!").unwrap();
+        verifier.finish().unwrap();
+
+        let mut verifier = key.verify(&jws).unwrap();
+        verifier.update("Hello world?").unwrap();
+        assert_eq!(verifier.finish(), Err(Error::Signature));
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn es256() {
+        roundtrip(
+            p256::SecretKey::random(&mut OsRng).into(),
+            Signing::Es256,
+            64,
+        );
+    }
+
+    #[cfg(feature = "p384")]
+    #[test]
+    fn es384() {
+        roundtrip(
+            p384::SecretKey::random(&mut OsRng).into(),
+            Signing::Es384,
+            96,
+        );
+    }
+
+    #[cfg(feature = "p521")]
+    #[test]
+    fn es512() {
+        roundtrip(
+            p521::SecretKey::random(&mut OsRng).into(),
+            Signing::Es512,
+            132,
+        );
+    }
+
+    #[cfg(feature = "k256")]
+    #[test]
+    fn es256k() {
+        roundtrip(
+            k256::SecretKey::random(&mut OsRng).into(),
+            Signing::Es256K,
+            64,
+        );
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn mismatch() {
+        use jose_jws::{Protected, Unprotected};
+
+        let key = p256::SecretKey::random(&mut OsRng);
+
+        let prot = Protected {
+            oth: Unprotected {
+                alg: Some(Signing::Es384),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            key.sign(Some(prot), None),
+            Err(Error::AlgMismatch)
+        ));
+
+        let mut signer = key.sign(None, None).unwrap();
+        signer.update("Hello world!").unwrap();
+        let signature = signer.finish(OsRng).unwrap();
+
+        let public = p256::SecretKey::random(&mut OsRng).public_key();
+        let mut verifier = public.verify(&signature).unwrap();
+        verifier.update("Hello world!").unwrap();
+        assert_eq!(verifier.finish(), Err(Error::Signature));
+    }
+}
+
+#[cfg(all(test, feature = "rsa"))]
+mod rsa {
+    use jose_b64::stream::Update;
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::{Key, Kind};
+    use jose_jwk::Jwk;
+    use jose_jws::crypto::{Error, Signer, SigningKey, Verifier, VerifyingKey};
+    use jose_jws::{Flattened, Protected, Unprotected};
+    use rand_core::OsRng;
+
+    const PAYLOAD: &str = "eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ";
+
+    /// The RSA key from https://datatracker.ietf.org/doc/html/rfc7517#appendix-A.2
+    fn key() -> Key {
+        let jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "RSA",
+            "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+            "e": "AQAB",
+            "d": "X4cTteJY_gn4FYPsXB8rdXix5vwsg1FLN5E3EaG6RJoVH-HLLKD9M7dx5oo7GURknchnrRweUkC7hT5fJLM0WbFAKNLWY2vv7B6NqXSzUvxT0_YSfqijwp3RTzlBaCxWp4doFk5N2o8Gy_nHNKroADIkJ46pRUohsXywbReAdYaMwFs9tv8d_cPVY3i07a3t8MN6TNwm0dSawm9v47UiCl3Sk5ZiG7xojPLu4sbg1U2jx4IBTNBznbJSzFHK66jT8bgkuqsk0GjskDJk19Z4qwjwbsnn4j2WBii3RL-Us2lGVkY8fkFzme1z0HbIkfz0Y6mqnOYtqc0X4jfcKoAC8Q",
+            "p": "83i-7IvMGXoMXCskv73TKr8637FiO7Z27zv8oj6pbWUQyLPQBQxtPVnwD20R-60eTDmD2ujnMt5PoqMrm8RfmNhVWDtjjMmCMjOpSXicFHj7XOuVIYQyqVWlWEh6dN36GVZYk93N8Bc9vY41xy8B9RzzOGVQzXvNEvn7O0nVbfs",
+            "q": "3dfOR9cuYq-0S-mkFLzgItgMEfFzB2q3hWehMuG0oCuqnb3vobLyumqjVZQO1dIrdwgTnCdpYzBcOfW5r370AFXjiWft_NGEiovonizhKpo9VVS78TzFgxkIdrecRezsZ-1kYd_s1qDbxtkDEgfAITAG9LUnADun4vIcb6yelxk",
+            "dp": "G4sPXkc6Ya9y8oJW9_ILj4xuppu0lzi_H7VTkS8xj5SdX3coE0oimYwxIi2emTAue0UOa5dpgFGyBJ4c8tQ2VF402XRugKDTP8akYhFo5tAA77Qe_NmtuYZc3C3m3I24G2GvR5sSDxUyAN2zq8Lfn9EUms6rY3Ob8YeiKkTiBj0",
+            "dq": "s9lAH9fggBsoFR8Oac2R_E2gw282rT2kGOAhvIllETE1efrA6huUUvMfBcMpn8lqeW6vzznYY5SSQF7pMdC_agI3nG8Ibp1BUb0JUiraRNqUfLhcQb_d9GF4Dh7e74WbRsobRonujTYN1xCaP6TO61jvWrX-L18txXw494Q_cgk",
+            "qi": "GyM_p6JrXySiz1toFgKbWV-JdI3jQ4ypu9rbMWx3rQJBfmt0FoYzgUIZEVFEcOqwemRN81zoDAaa-Bk0KWNGDjJHZDdDmFhW3AN7lI-puxk_mHZGJ11rxyR8O55XLSe3SPmRfKwZI6yU24ZxvQKFYItdldUKGzO6Ia6zTKhAVRU",
+        }))
+        .unwrap();
+
+        Key::try_from(&jwk.key).unwrap()
+    }
+
+    /// Private RSA keys must be larger for RS384 and above; verify with the
+    /// public half instead.
+    fn public() -> Key {
+        match key() {
+            Key::Rsa(Kind::Secret(key)) => Key::from(key.to_public_key()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn protected(alg: Signing) -> Protected {
+        Protected {
+            oth: Unprotected {
+                alg: Some(alg),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn verify(key: &Key, jws: &str) -> Result<(), Error> {
+        let jws: Flattened = jws.parse().unwrap();
+
+        let mut verifier = key.verify(&jws)?;
+        verifier.update(jws.payload.as_ref().unwrap())?;
+        verifier.finish()
+    }
+
+    /// PKCS#1 v1.5 signatures are deterministic, so the output can be
+    /// compared against an independent implementation (pyca/cryptography).
+    #[test]
+    fn rs256() {
+        let key = key();
+        let payload: Flattened = format!("e30.{PAYLOAD}.AA").parse().unwrap();
+        let payload = payload.payload.unwrap();
+
+        let mut signer = key.sign(Some(protected(Signing::Rs256)), None).unwrap();
+        signer.update(&payload).unwrap();
+        let jws = Flattened {
+            payload: Some(payload),
+            signature: signer.finish(OsRng).unwrap(),
+        };
+
+        let jws = jws.to_string();
+        assert_eq!(
+            jws,
+            format!(
+                "eyJhbGciOiJSUzI1NiJ9.{PAYLOAD}.\
+                DS0kcM3KbMwJWyxmJ2NWC21HGx93MXy9sSgsVygnx4U7XKayfNACjigqZL9jH-UL1MjIIXVUmaVc5ljgt84f\
+                jhlfcMdJ67Q2_tyyUdbOjPrVfcDnpwpxKQQ2tA9fpHFQL_JENgraWFJQ1O27WKDvYfsRmj-Z2xIJzYETdZyk\
+                NKS4lcN-B-eusA2zw9iUnl3TdAdSIKr7QrTZrd3Osema_hCSCfD1faLWGUhRMHnx5eSxbDogV0-7P0OUHDP0\
+                IoxWGNcrAQ7vTBlEAg92LhGN8JGW2k-bludnJb5gBJrauMYxqi9d4ajKYka0GSaky4CpjMOpexkkGORk2VC8\
+                wiNMFg"
+            )
+        );
+
+        verify(&key, &jws).unwrap();
+    }
+
+    #[test]
+    fn rs512() {
+        let jws = format!(
+            "eyJhbGciOiJSUzUxMiJ9.{PAYLOAD}.\
+            LGVWU_-vvhBhIFNtqJWsiz_mFcKRfSiIEg6uRFlHIUK0fILFlBYgk18y978Knlv62WeX5PgOdOajkjTRwIX0\
+            Q8m9mWAoBeeQ60xHkj9O9Rs_AolbU8afWOhsNIpPIP00-_UF1mQ8NgK2apH-MskwH9-0xuVUvrXmpXmFsLbw\
+            UrRbd_O2j9yusBe6EMrW4qvEAgChCM10tO9ZGcxqph9F8boedpTsoBZubuOKzcfYxinCIjrhUcwktXEld8U4\
+            hhODiOfqrem7oApsNr63E_4ghxtTgELOxtNXyNiCQL0W14_jcttpLP0UpGjFa_9B6HU5V1d6F3Wg_1snBYk1\
+            SeH53w"
+        );
+
+        verify(&public(), &jws).unwrap();
+    }
+
+    #[test]
+    fn ps384() {
+        let jws = format!(
+            "eyJhbGciOiJQUzM4NCJ9.{PAYLOAD}.\
+            PcEcHHgXz-PShw0XCp4ijLIX-dqVOM1KOmo0_MqqTmF3c1xTdsfIY8mu7Bj_ZltiznCM6jQPI9pln47BeYbC\
+            oQS1pDyXLIhu8fASwyH8I1ZWMi-d65nZScUD74-eM4Q_8xJ0YejEwjOIfLNtB6iQPWa2cgbCkJmLPM_z4WCq\
+            NQv3caUh_DROd3wCWKWwhtvMjDB0NneqZbnpvo8K7WXnaF1K0ij4g4XyxZwIiDR_uhlAuW-LD-IWxeDEsNT3\
+            OOQGn30Z9Zje3KqvzJVojdNU0YA_VffLRbQRDD1u5peRh4h60PB6gp_vZ52v5BSjKQYnrcpW96Qcp8qnzFDF\
+            hZ8xwQ"
+        );
+
+        verify(&public(), &jws).unwrap();
+    }
+
+    #[test]
+    fn ps256() {
+        let key = key();
+
+        let mut signer = key.sign(Some(protected(Signing::Ps256)), None).unwrap();
+        signer.update("Hello world!").unwrap();
+        let jws = Flattened {
+            payload: Some(b"Hello world!".to_vec().into()),
+            signature: signer.finish(OsRng).unwrap(),
+        };
+
+        verify(&key, &jws.to_string()).unwrap();
+
+        let mut verifier = key.verify(&jws).unwrap();
+        verifier.update("Hello world?").unwrap();
+        assert_eq!(verifier.finish(), Err(Error::Signature));
+    }
+
+    #[test]
+    fn unspecified() {
+        assert!(matches!(key().sign(None, None), Err(Error::Malformed)));
+    }
+
+    #[test]
+    fn weak() {
+        let key = Key::from(::rsa::RsaPrivateKey::new(&mut OsRng, 1024).unwrap());
+        let prot = protected(Signing::Rs256);
+        assert!(matches!(key.sign(Some(prot), None), Err(Error::WeakKey)));
+
+        let jws = format!("eyJhbGciOiJSUzI1NiJ9.{PAYLOAD}.AAAA");
+        assert_eq!(verify(&key, &jws), Err(Error::WeakKey));
+    }
+}
+
+#[cfg(all(test, feature = "hmac"))]
+mod hmac {
+    use jose_b64::stream::Update;
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::Key;
+    use jose_jws::crypto::{Error, Signer, SigningKey, Verifier, VerifyingKey};
+    use jose_jws::{Flattened, Protected, Unprotected};
+    use rand_core::OsRng;
+
+    fn protected(alg: Signing) -> Protected {
+        Protected {
+            oth: Unprotected {
+                alg: Some(alg),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn roundtrip(alg: Signing, len: usize) {
+        let key = Key::Oct(vec![0x55; len].into_boxed_slice().into());
+
+        let mut signer = key.sign(Some(protected(alg)), None).unwrap();
+        signer.update("Hello world!").unwrap();
+        let signature = signer.finish(OsRng).unwrap();
+        assert_eq!(signature.signature.len(), len);
+
+        let mut jws = Flattened {
+            payload: Some(b"Hello world!".to_vec().into()),
+            signature,
+        };
+
+        let mut verifier = key.verify(&jws).unwrap();
+        verifier.update("Hello world!").unwrap();
+        verifier.finish().unwrap();
+
+        let mut verifier = key.verify(&jws).unwrap();
+        verifier.update("Hello world?").unwrap();
+        assert_eq!(verifier.finish(), Err(Error::Signature));
+
+        let mut truncated = jws.signature.signature.to_vec();
+        truncated.truncate(len / 2);
+        jws.signature.signature = truncated.into();
+
+        let mut verifier = key.verify(&jws).unwrap();
+        verifier.update("Hello world!").unwrap();
+        assert_eq!(verifier.finish(), Err(Error::Signature));
+    }
+
+    #[test]
+    fn hs256() {
+        roundtrip(Signing::Hs256, 32);
+    }
+
+    #[test]
+    fn hs384() {
+        roundtrip(Signing::Hs384, 48);
+    }
+
+    #[test]
+    fn hs512() {
+        roundtrip(Signing::Hs512, 64);
+    }
+
+    #[test]
+    fn misuse() {
+        let key = Key::Oct(vec![0x55; 16].into_boxed_slice().into());
+        assert!(matches!(key.sign(None, None), Err(Error::Malformed)));
+
+        let prot = protected(Signing::Hs512);
+        assert!(matches!(key.sign(Some(prot), None), Err(Error::WeakKey)));
+
+        let prot = protected(Signing::Es256);
+        assert!(matches!(
+            key.sign(Some(prot), None),
+            Err(Error::AlgMismatch)
+        ));
+    }
+}
+
+#[cfg(all(test, any(feature = "ed25519", feature = "ed448")))]
+mod eddsa {
+    use jose_b64::stream::Update;
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::Key;
+    use jose_jws::crypto::{Error, Signer, SigningKey, Verifier, VerifyingKey};
+    use jose_jws::Flattened;
+    use rand_core::OsRng;
+
+    fn sign(key: &Key, payload: &[u8]) -> Flattened {
+        let mut signer = key.sign(None, None).unwrap();
+        signer.update(payload).unwrap();
+        let signature = signer.finish(OsRng).unwrap();
+
+        let prot = signature.protected.as_ref().unwrap();
+        assert_eq!(prot.oth.alg, Some(Signing::EdDsa));
+
+        Flattened {
+            payload: Some(payload.to_vec().into()),
+            signature,
+        }
+    }
+
+    fn verify(key: &Key, jws: &Flattened, payload: &[u8]) -> Result<(), Error> {
+        let mut verifier = key.verify(jws)?;
+        verifier.update(payload)?;
+        verifier.finish()
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc8037#appendix-A.4
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn rfc8037_a4() {
+        use jose_jwk::Jwk;
+
+        let jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "d": "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+        }))
+        .unwrap();
+        let key = Key::try_from(&jwk.key).unwrap();
+
+        let jws = sign(&key, b"Example of Ed25519 signing");
+        assert_eq!(
+            jws.to_string(),
+            "eyJhbGciOiJFZERTQSJ9\
+            .RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc\
+            .hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6dWbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg"
+        );
+
+        // RFC 8037 Appendix A.5 verifies with the public key only.
+        let jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+        }))
+        .unwrap();
+        let public = Key::try_from(&jwk.key).unwrap();
+        verify(&public, &jws, b"Example of Ed25519 signing").unwrap();
+        assert_eq!(
+            verify(&public, &jws, b"Example of Ed25519 signinG"),
+            Err(Error::Signature)
+        );
+    }
+
+    #[cfg(feature = "ed448")]
+    #[test]
+    fn ed448() {
+        let key = Key::from(ed448_goldilocks_plus::SigningKey::generate(OsRng));
+
+        let jws = sign(&key, b"Hello world!");
+        assert_eq!(jws.signature.signature.len(), 114);
+
+        let jws: Flattened = jws.to_string().parse().unwrap();
+        verify(&key, &jws, b"Hello world!").unwrap();
+        assert_eq!(verify(&key, &jws, b"Hello world?"), Err(Error::Signature));
+
+        let other = Key::from(ed448_goldilocks_plus::SigningKey::generate(OsRng));
+        assert_eq!(verify(&other, &jws, b"Hello world!"), Err(Error::Signature));
+    }
+}
+
+#[cfg(all(test, feature = "p256", feature = "hmac"))]
+mod oneshot {
+    use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+    use jose_b64::stream::Update;
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::{Key, Restricted};
+    use jose_jwk::{Ec, Jwk, JwkSet, Parameters};
+    use jose_jws::crypto::{
+        candidates, resolve, sign, sign_all, verify, verify_jwks, verify_policy, DetachedVerifier,
+        Error, GeneralSigner, KeySigner, Policy, Verifier, VerifyingKey,
+    };
+    use jose_jws::{Jws, Protected, Signature, Unprotected};
+    use rand_core::OsRng;
+
+    const ALGS: &[Signing] = &[Signing::Es256, Signing::Hs256, Signing::Rs256];
+
+    fn header(kid: &str) -> Option<Unprotected> {
+        Some(Unprotected {
+            kid: Some(kid.into()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn flattened() {
+        let key = Key::from(p256::SecretKey::random(&mut OsRng));
+        let jws = sign(&key, None, header("a"), b"Hello world!", OsRng).unwrap();
+        assert!(matches!(jws, Jws::Flattened(..)));
+
+        let jws: Jws = serde_json::from_str(&serde_json::to_string(&jws).unwrap()).unwrap();
+        let verified = verify(&key, &jws, ALGS, &[]).unwrap();
+        assert_eq!(verified.payload, b"Hello world!");
+        assert_eq!(verified.protected.unwrap().oth.alg, Some(Signing::Es256));
+        assert_eq!(verified.header.unwrap().kid.as_deref(), Some("a"));
+
+        let other = Key::from(p256::SecretKey::random(&mut OsRng));
+        assert_eq!(
+            verify(&other, &jws, ALGS, &[]).unwrap_err(),
+            Error::Signature
+        );
+    }
+
+    #[test]
+    fn general() {
+        let ec = Key::from(p256::SecretKey::random(&mut OsRng));
+        let oct = Key::Oct(vec![0x55; 32].into_boxed_slice().into());
+        let hs256 = Protected {
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let keys = [
+            (&ec, None, header("ec")),
+            (&oct, Some(hs256), header("oct")),
+        ];
+        let jws = sign_all(keys, b"Hello world!", OsRng).unwrap();
+        let Jws::General(general) = &jws else {
+            panic!("expected the general serialization");
+        };
+        assert_eq!(general.signatures.len(), 2);
+
+        let verified = verify(&oct, &jws, ALGS, &[]).unwrap();
+        assert_eq!(verified.payload, b"Hello world!");
+        assert_eq!(verified.header.unwrap().kid.as_deref(), Some("oct"));
+
+        let verified = verify(&ec, &jws, ALGS, &[]).unwrap();
+        assert_eq!(verified.header.unwrap().kid.as_deref(), Some("ec"));
+
+        // Every signature reports why it failed.
+        let other = Key::from(p256::SecretKey::random(&mut OsRng));
+        assert_eq!(
+            verify(&other, &jws, ALGS, &[]).unwrap_err(),
+            Error::Signatures(vec![Error::Signature, Error::AlgMismatch])
+        );
+
+        let none: [(&Key, _, _); 0] = [];
+        assert!(matches!(
+            sign_all(none, b"Hello world!", OsRng),
+            Err(Error::Malformed)
+        ));
+    }
+
+    #[test]
+    fn builder() {
+        let ec = Key::from(p256::SecretKey::random(&mut OsRng));
+        let oct = Key::Oct(vec![0x55; 32].into_boxed_slice().into());
+        let hs256 = Protected {
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                kid: Some("oct".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let general = GeneralSigner::new()
+            .add(&ec, None, header("ec"))
+            .unwrap()
+            .add(&oct, Some(hs256), None)
+            .unwrap()
+            .chain("Hello")
+            .unwrap()
+            .chain(" world!")
+            .unwrap()
+            .finish(OsRng)
+            .unwrap();
+        assert_eq!(&general.payload.as_ref().unwrap()[..], b"Hello world!");
+        assert_eq!(general.signatures.len(), 2);
+
+        let jws = Jws::General(general);
+        let jws: Jws = serde_json::from_str(&serde_json::to_string(&jws).unwrap()).unwrap();
+
+        let verified = verify(&ec, &jws, ALGS, &[]).unwrap();
+        assert_eq!(verified.header.unwrap().kid.as_deref(), Some("ec"));
+
+        let verified = verify(&oct, &jws, ALGS, &[]).unwrap();
+        assert_eq!(verified.protected.unwrap().oth.kid.as_deref(), Some("oct"));
+
+        let empty = GeneralSigner::<KeySigner<'_>>::new();
+        assert_eq!(empty.finish(OsRng).unwrap_err(), Error::Malformed);
+
+        // The keys share the payload, so they must agree on `b64`.
+        let unencoded = Protected {
+            b64: false,
+            crit: Some(vec!["b64".into()]),
+            ..Default::default()
+        };
+        let err = GeneralSigner::new()
+            .add(&ec, None, header("ec"))
+            .unwrap()
+            .add(&oct, Some(unencoded), None)
+            .err();
+        assert_eq!(err, Some(Error::Malformed));
+    }
+
+    #[test]
+    fn policy() {
+        fn kid(kid: &str) -> Option<Protected> {
+            Some(Protected {
+                oth: Unprotected {
+                    kid: Some(kid.into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        }
+
+        let sa = p256::SecretKey::random(&mut OsRng);
+        let sb = p256::SecretKey::random(&mut OsRng);
+        let (a, b) = (Key::from(sa.clone()), Key::from(sb.clone()));
+
+        let keys = [(&a, kid("a"), None), (&b, kid("b"), None)];
+        let jws = sign_all(keys, b"Hello world!", OsRng).unwrap();
+
+        let both = [(Some("a"), sa.public_key()), (Some("b"), sb.public_key())];
+        let report = verify_policy(&both, &jws, &Policy::All, ALGS, &[]).unwrap();
+        assert_eq!(
+            report.passed().map(|(i, x)| (i, x.key)).collect::<Vec<_>>(),
+            [(0, 0), (1, 1)]
+        );
+        assert!(verify_policy(&both, &jws, &Policy::Threshold(2), ALGS, &[]).is_ok());
+
+        let kids = Policy::Kids(vec!["a".into(), "b".into()]);
+        assert!(verify_policy(&both, &jws, &kids, ALGS, &[]).is_ok());
+
+        let c = Key::Oct(vec![0x55; 32].into_boxed_slice().into());
+        let one = [(Some("c"), c), (Some("a"), Key::from(sa.public_key()))];
+        let report = verify_policy(&one, &jws, &Policy::All, ALGS, &[]).unwrap_err();
+        assert_eq!(
+            report.passed().map(|(i, x)| (i, x.key)).collect::<Vec<_>>(),
+            [(0, 1)]
+        );
+        assert_eq!(
+            report.failed().collect::<Vec<_>>(),
+            [(
+                1,
+                &Error::Signatures(vec![Error::AlgMismatch, Error::Signature])
+            )]
+        );
+        assert!(verify_policy(&one, &jws, &Policy::Any, ALGS, &[]).is_ok());
+        assert!(verify_policy(&one, &jws, &Policy::Threshold(1), ALGS, &[]).is_ok());
+        assert!(verify_policy(&one, &jws, &Policy::Threshold(2), ALGS, &[]).is_err());
+        assert!(verify_policy(&one, &jws, &Policy::Kids(vec!["a".into()]), ALGS, &[]).is_ok());
+        assert!(verify_policy(&one, &jws, &Policy::Kids(vec!["b".into()]), ALGS, &[]).is_err());
+
+        // Two signatures by the same key only count once towards a threshold.
+        let keys = [(&a, kid("a"), None), (&a, kid("b"), None)];
+        let jws = sign_all(keys, b"Hello world!", OsRng).unwrap();
+        let only = [(Some("a"), sa.public_key())];
+        assert!(verify_policy(&only, &jws, &Policy::All, ALGS, &[]).is_ok());
+        assert!(verify_policy(&both, &jws, &Policy::Threshold(2), ALGS, &[]).is_err());
+
+        // The key identifier is that of the verifying key, not the one named
+        // by the signature: key A signing with the `kid` of B does not count.
+        let keys = [(&a, kid("b"), None)];
+        let jws = sign_all(keys, b"Hello world!", OsRng).unwrap();
+        let kids = Policy::Kids(vec!["b".into()]);
+        let report = verify_policy(&both, &jws, &kids, ALGS, &[]).unwrap_err();
+        assert_eq!(report.passed().next().unwrap().1.kid, Some("a"));
+        assert!(verify_policy(&both, &jws, &Policy::Kids(vec!["a".into()]), ALGS, &[]).is_ok());
+    }
+
+    #[test]
+    fn crit() {
+        let key = Key::from(p256::SecretKey::random(&mut OsRng));
+        let sign = |crit: Option<Vec<String>>, b64| {
+            let prot = Protected {
+                crit,
+                b64,
+                ..Default::default()
+            };
+            sign(&key, Some(prot), None, b"Hello world!", OsRng)
+        };
+
+        let jws = sign(Some(vec!["b64".into()]), false).unwrap();
+        verify(&key, &jws, ALGS, &[]).unwrap();
+
+        for crit in [vec![], vec!["alg".into()], vec!["b64".into(), "b64".into()]] {
+            let jws = sign(Some(crit), true).unwrap();
+            assert_eq!(verify(&key, &jws, ALGS, &[]).unwrap_err(), Error::Critical);
+        }
+
+        // The extension must be present in the protected header.
+        let jws = sign(Some(vec!["b64".into()]), true).unwrap();
+        assert_eq!(verify(&key, &jws, ALGS, &[]).unwrap_err(), Error::Critical);
+
+        // From https://datatracker.ietf.org/doc/html/rfc7515#section-4.1.11
+        let mut val = serde_json::to_value(sign(None, true).unwrap()).unwrap();
+        val["protected"] =
+            Base64UrlUnpadded::encode_string(br#"{"alg":"ES256","crit":["exp"],"exp":1363284000}"#)
+                .into();
+        let jws: Jws = serde_json::from_value(val).unwrap();
+        assert_eq!(verify(&key, &jws, ALGS, &[]).unwrap_err(), Error::Critical);
+        assert_eq!(
+            verify(&key, &jws, ALGS, &["exp"]).unwrap_err(),
+            Error::Signature
+        );
+
+        // `crit` must not appear in the unprotected header.
+        let head = Unprotected {
+            crit: Some(vec!["b64".into()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            jose_jws::crypto::sign(&key, None, Some(head.clone()), b"", OsRng).unwrap_err(),
+            Error::Critical
+        );
+
+        let mut jws = sign(None, true).unwrap();
+        if let Jws::Flattened(flattened) = &mut jws {
+            flattened.signature.header = Some(head);
+        }
+        let jws: Jws = serde_json::from_str(&serde_json::to_string(&jws).unwrap()).unwrap();
+        assert_eq!(verify(&key, &jws, ALGS, &[]).unwrap_err(), Error::Critical);
+    }
+
+    #[test]
+    fn detached() {
+        let key = Key::from(p256::SecretKey::random(&mut OsRng));
+        let mut jws = sign(&key, None, None, b"Hello world!", OsRng).unwrap();
+        if let Jws::Flattened(flattened) = &mut jws {
+            flattened.payload = None;
+        }
+
+        assert_eq!(verify(&key, &jws, ALGS, &[]).unwrap_err(), Error::Malformed);
+
+        let verifier = DetachedVerifier::new(&key, &jws, ALGS, &[]);
+        let sig = verifier.chain("Hello").unwrap().chain(" world!").unwrap();
+        assert!(sig.finish().unwrap().protected.is_some());
+
+        let verifier = DetachedVerifier::new(&key, &jws, ALGS, &[]);
+        let verifier = verifier.chain("Hello world?").unwrap();
+        assert_eq!(verifier.finish().unwrap_err(), Error::Signature);
+
+        #[cfg(feature = "std")]
+        {
+            let mut verifier = DetachedVerifier::new(&key, &jws, ALGS, &[]);
+            let len = verifier.read(&b"Hello world!"[..]).unwrap();
+            assert_eq!(len, 12);
+            verifier.finish().unwrap();
+        }
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.6
+    #[test]
+    fn jwks() {
+        let jws: Jws = serde_json::from_value(serde_json::json!({
+            "payload": "eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ",
+            "signatures": [
+                {
+                    "protected": "eyJhbGciOiJSUzI1NiJ9",
+                    "header": { "kid": "2010-12-29" },
+                    "signature": "cC4hiUPoj9Eetdgtv3hF80EGrhuB__dzERat0XF9g2VtQgr9PJbu3XOiZj5RZmh7\
+                        AAuHIm4Bh-0Qc_lF5YKt_O8W2Fp5jujGbds9uJdbF9CUAr7t1dnZcAcQjbKBYNX4\
+                        BAynRFdiuB--f_nZLgrnbyTyWzO75vRK5h6xBArLIARNPvkSjtQBMHlb1L07Qe7K\
+                        0GarZRmB_eSN9383LcOLn6_dO--xi12jzDwusC-eOkHWEsqtFZESc6BfI7noOPqv\
+                        hJ1phCnvWh6IeYI2w9QOYEUipUTI8np6LbgGY9Fs98rqVt5AXLIhWkWywlVmtVrB\
+                        p0igcN_IoypGlUPQGe77Rw"
+                },
+                {
+                    "protected": "eyJhbGciOiJFUzI1NiJ9",
+                    "header": { "kid": "e9bc097a-ce51-4036-9562-d2ade882db0d" },
+                    "signature": "DtEhU3ljbEg8L38VWAfUAqOyKAM6-Xx-F4GawxaepmXFCgfTjDxw5djxLa8IS\
+                        lSApmWQxfKTUJqPP3-Kg6NU1Q"
+                }
+            ]
+        }))
+        .unwrap();
+
+        let jwk = |kid: &str| -> Jwk {
+            serde_json::from_value(serde_json::json!({
+                "kty": "EC",
+                "crv": "P-256",
+                "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+                "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0",
+                "kid": kid
+            }))
+            .unwrap()
+        };
+
+        let jwks = JwkSet {
+            keys: vec![
+                jwk("2010-12-29"),
+                jwk("e9bc097a-ce51-4036-9562-d2ade882db0d"),
+            ],
+        };
+        let verified = verify_jwks(&jwks, &jws, ALGS, &[]).unwrap();
+        assert_eq!(
+            verified.header.unwrap().kid.as_deref(),
+            Some("e9bc097a-ce51-4036-9562-d2ade882db0d")
+        );
+
+        // The key is only tried for signatures naming its `kid`.
+        let jwks = JwkSet {
+            keys: vec![jwk("unrelated")],
+        };
+        assert_eq!(
+            verify_jwks(&jwks, &jws, ALGS, &[]).unwrap_err(),
+            Error::Signatures(vec![Error::NoKey, Error::NoKey])
+        );
+    }
+
+    #[test]
+    fn algs() {
+        let oct = serde_json::json!({
+            "kty": "oct",
+            "k": "VVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVU"
+        });
+        let key = Key::try_from(&serde_json::from_value::<Jwk>(oct.clone()).unwrap().key).unwrap();
+
+        let prot = Protected {
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let jws = sign(&key, Some(prot), None, b"Hello world!", OsRng).unwrap();
+        verify(&key, &jws, &[Signing::Hs256], &[]).unwrap();
+
+        // The header cannot choose an algorithm which is not allowed.
+        let err = verify(&key, &jws, &[Signing::Rs256], &[]).unwrap_err();
+        assert_eq!(err, Error::Policy);
+
+        // The JWK parameters must permit the algorithm and verification.
+        let jwk = |prm: serde_json::Value| -> Jwk {
+            let mut jwk = oct.clone();
+            jwk.as_object_mut()
+                .unwrap()
+                .extend(prm.as_object().unwrap().clone());
+            serde_json::from_value(jwk).unwrap()
+        };
+
+        let ok = jwk(serde_json::json!({ "alg": "HS256", "use": "sig", "key_ops": ["verify"] }));
+        verify(&ok, &jws, ALGS, &[]).unwrap();
+
+        let cases = [
+            (serde_json::json!({ "alg": "HS512" }), Error::AlgMismatch),
+            (serde_json::json!({ "use": "enc" }), Error::Operation),
+            (serde_json::json!({ "key_ops": ["sign"] }), Error::Operation),
+        ];
+        for (prm, err) in cases {
+            let jwk = jwk(prm);
+            assert_eq!(verify(&jwk, &jws, ALGS, &[]).unwrap_err(), err);
+        }
+
+        // Streaming with a JWK needs a pinned `alg` or an allow-list.
+        let Jws::Flattened(flattened) = &jws else {
+            unreachable!()
+        };
+        let bare = jwk(serde_json::json!({}));
+        let err = VerifyingKey::<&Signature>::verify(&bare, &flattened.signature).err();
+        assert_eq!(err, Some(Error::Policy));
+
+        let err = bare.verify_allowed(&jws, &[Signing::Rs256]).err();
+        assert_eq!(err, Some(Error::Policy));
+
+        let mut verifier = bare.verify_allowed(&jws, &[Signing::Hs256]).unwrap();
+        verifier.update(b"Hello world!").unwrap();
+        verifier.finish().unwrap();
+
+        let mut verifier = VerifyingKey::<&Jws>::verify(&ok, &jws).unwrap();
+        verifier.update(b"Hello world!").unwrap();
+        verifier.finish().unwrap();
+    }
+
+    #[test]
+    fn restricted() {
+        let key = |prm: serde_json::Value| -> Restricted {
+            let mut jwk = serde_json::json!({
+                "kty": "oct",
+                "k": "VVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVU"
+            });
+            jwk.as_object_mut()
+                .unwrap()
+                .extend(prm.as_object().unwrap().clone());
+            Restricted::try_from(&serde_json::from_value::<Jwk>(jwk).unwrap()).unwrap()
+        };
+
+        let prot = Some(Protected {
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let sig = key(serde_json::json!({ "use": "sig" }));
+        let jws = sign(&sig, prot.clone(), None, b"Hello world!", OsRng).unwrap();
+        verify(&sig, &jws, ALGS, &[]).unwrap();
+
+        // A key which may only verify refuses to sign.
+        let ver = key(serde_json::json!({ "key_ops": ["verify"] }));
+        verify(&ver, &jws, ALGS, &[]).unwrap();
+        let err = sign(&ver, prot.clone(), None, b"Hello world!", OsRng).unwrap_err();
+        assert_eq!(err, Error::Operation);
+
+        // An encryption key can neither sign nor verify.
+        let enc = key(serde_json::json!({ "use": "enc" }));
+        let err = sign(&enc, prot, None, b"Hello world!", OsRng).unwrap_err();
+        assert_eq!(err, Error::Operation);
+        assert_eq!(verify(&enc, &jws, ALGS, &[]).unwrap_err(), Error::Operation);
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7519#section-6.1
+    #[test]
+    fn unsecured() {
+        let jws: Jws = "eyJhbGciOiJub25lIn0\
+            .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+            ."
+            .parse()
+            .unwrap();
+
+        let keys = [(None, Key::from(p256::SecretKey::random(&mut OsRng)))];
+        assert_eq!(
+            verify(&keys[0].1, &jws, ALGS, &[]).unwrap_err(),
+            Error::Policy
+        );
+
+        // Unsecured signatures are only accepted when explicitly allowed.
+        let none = &[Signing::Null];
+        let verified = verify(&keys[0].1, &jws, none, &[]).unwrap();
+        assert!(verified.payload.starts_with(br#"{"iss":"joe""#));
+
+        let report = verify_policy(&keys, &jws, &Policy::Any, none, &[]).unwrap_err();
+        assert_eq!(report.failed().collect::<Vec<_>>(), [(0, &Error::Policy)]);
+
+        // An unsecured signature must be empty.
+        let Jws::Flattened(mut flattened) = jws else {
+            panic!("expected the flattened serialization");
+        };
+        flattened.signature.signature = b"forged".to_vec().into();
+        let err = verify(&keys[0].1, &flattened.into(), none, &[]).unwrap_err();
+        assert_eq!(err, Error::Signature);
+    }
+
+    #[test]
+    fn resolver() {
+        let sa = p256::SecretKey::random(&mut OsRng);
+        let sb = p256::SecretKey::random(&mut OsRng);
+
+        let jwk = |key: &p256::SecretKey, prm: Parameters| Jwk {
+            key: jose_jwk::Key::Ec(Ec::from(key.public_key())),
+            prm,
+        };
+        let kid = |kid: &str| Parameters {
+            kid: Some(kid.into()),
+            ..Default::default()
+        };
+
+        let mut b = kid("b");
+        b.x5t.s256 = Some([7; 32].into());
+
+        let oct: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "oct",
+            "k": "VVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVVU"
+        }))
+        .unwrap();
+
+        let jwks = JwkSet {
+            keys: vec![jwk(&sa, kid("a")), jwk(&sb, b), oct],
+        };
+
+        let signature = |key: &p256::SecretKey, head: Option<Unprotected>| -> Signature {
+            match sign(&Key::from(key.clone()), None, head, b"Hello world!", OsRng).unwrap() {
+                Jws::Flattened(x) => x.signature,
+                _ => unreachable!(),
+            }
+        };
+
+        // The `kid` selects a single key.
+        let sig = signature(&sa, header("a"));
+        assert_eq!(resolve(&jwks, &sig).unwrap().prm.kid.as_deref(), Some("a"));
+
+        let sig = signature(&sa, header("c"));
+        assert_eq!(resolve(&jwks, &sig).unwrap_err(), Error::NoKey);
+
+        // Without hints, every EC key matches, but not the HMAC key.
+        let sig = signature(&sa, None);
+        assert_eq!(candidates(&jwks, &sig).unwrap().len(), 2);
+        assert_eq!(resolve(&jwks, &sig).unwrap_err(), Error::Ambiguous);
+
+        // The X.509 thumbprint selects a single key.
+        let mut head = Unprotected::default();
+        head.x5t.s256 = Some([7; 32].into());
+        let sig = signature(&sb, Some(head));
+        assert_eq!(resolve(&jwks, &sig).unwrap().prm.kid.as_deref(), Some("b"));
+
+        // An embedded key selects the key of the set with its thumbprint.
+        let head = Unprotected {
+            jwk: Some(jwk(&sb, Parameters::default())),
+            ..Default::default()
+        };
+        let sig = signature(&sb, Some(head));
+        assert_eq!(resolve(&jwks, &sig).unwrap().prm.kid.as_deref(), Some("b"));
+
+        let head = Unprotected {
+            jwk: Some(jwk(
+                &p256::SecretKey::random(&mut OsRng),
+                Parameters::default(),
+            )),
+            ..Default::default()
+        };
+        let sig = signature(&sb, Some(head));
+        assert_eq!(resolve(&jwks, &sig).unwrap_err(), Error::NoKey);
+    }
+}
+
+#[cfg(all(test, feature = "hmac"))]
+mod rfc7797 {
+    use jose_b64::stream::Update;
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::Key;
+    use jose_jwk::Jwk;
+    use jose_jws::crypto::{sign, verify, verify_detached, DetachedVerifier, Error};
+    use jose_jws::{CompactError, Flattened, General, Jws, Protected, Unprotected};
+    use rand_core::OsRng;
+
+    const ALGS: &[Signing] = &[Signing::Hs256];
+
+    /// The HMAC key from https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.1
+    fn key() -> Key {
+        let jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "oct",
+            "k": "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"
+        }))
+        .unwrap();
+
+        Key::try_from(&jwk.key).unwrap()
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7797#section-4.1
+    #[test]
+    fn s4_1() {
+        let jws: Jws = "eyJhbGciOiJIUzI1NiJ9.JC4wMg.5mvfOroL-g7HyqJoozehmsaqmvTYGEq5jTI1gVvoEoQ"
+            .parse()
+            .unwrap();
+
+        assert_eq!(verify(&key(), &jws, ALGS, &[]).unwrap().payload, b"$.02");
+    }
+
+    /// The `b64` header parameter is only written when it is `false`, as
+    /// its default is `true` (RFC 7797 Section 3).
+    #[test]
+    fn b64_header() {
+        let prot = Protected::default();
+        let val = serde_json::to_value(&prot).unwrap();
+        assert!(val.get("b64").is_none());
+
+        let prot = Protected {
+            b64: false,
+            ..Default::default()
+        };
+        let val = serde_json::to_value(&prot).unwrap();
+        assert_eq!(val["b64"], false);
+
+        let prot: Protected = serde_json::from_value(val).unwrap();
+        assert!(!prot.b64);
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7797#section-4.2
+    #[test]
+    fn s4_2() {
+        const COMPACT: &str = "eyJhbGciOiJIUzI1NiIsImI2NCI6ZmFsc2UsImNyaXQiOlsiYjY0Il19\
+            ..A5dxf2s96_n5FLueVuW1Z_vh161FwXZC4YLPff6dmDY";
+
+        let flattened: Flattened = COMPACT.parse().unwrap();
+        assert!(flattened.payload.is_none());
+        assert_eq!(flattened.to_string(), COMPACT);
+
+        let prot = flattened.signature.protected.as_ref().unwrap();
+        assert!(!prot.b64);
+        assert_eq!(prot.crit, Some(vec!["b64".to_string()]));
+
+        let jws = Jws::from(flattened);
+        assert_eq!(
+            verify(&key(), &jws, ALGS, &[]).unwrap_err(),
+            Error::Malformed
+        );
+        verify_detached(&key(), &jws, b"$.02", ALGS, &[]).unwrap();
+
+        let hmac = key();
+        let verifier = DetachedVerifier::new(&hmac, &jws, ALGS, &[]);
+        let verifier = verifier.chain("$.").unwrap().chain("02").unwrap();
+        verifier.finish().unwrap();
+        assert_eq!(
+            verify_detached(&key(), &jws, b"$.03", ALGS, &[]).unwrap_err(),
+            Error::Signature
+        );
+
+        // The unencoded payload appears as-is in the JSON serialization.
+        let val = serde_json::json!({
+            "protected": "eyJhbGciOiJIUzI1NiIsImI2NCI6ZmFsc2UsImNyaXQiOlsiYjY0Il19",
+            "payload": "$.02",
+            "signature": "A5dxf2s96_n5FLueVuW1Z_vh161FwXZC4YLPff6dmDY"
+        });
+
+        let jws: Jws = serde_json::from_value(val.clone()).unwrap();
+        assert_eq!(verify(&key(), &jws, ALGS, &[]).unwrap().payload, b"$.02");
+
+        let Jws::Flattened(flattened) = &jws else {
+            panic!("expected the flattened serialization");
+        };
+        let mut out = serde_json::to_value(flattened).unwrap();
+        out.as_object_mut().unwrap().remove("header");
+        assert_eq!(out, val);
+
+        // A detached payload is omitted from the JSON serialization.
+        let detached: Flattened = COMPACT.parse().unwrap();
+        let val = serde_json::to_value(&detached).unwrap();
+        assert!(val.get("payload").is_none());
+        let parsed: Flattened = serde_json::from_value(val).unwrap();
+        assert!(parsed.payload.is_none());
+
+        let general = General::from(detached);
+        let val = serde_json::to_value(&general).unwrap();
+        assert!(val.get("payload").is_none());
+        let parsed: General = serde_json::from_value(val).unwrap();
+        assert!(parsed.payload.is_none());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let key = key();
+        let prot = Protected {
+            b64: false,
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let jws = sign(&key, Some(prot.clone()), None, b"Hello world!", OsRng).unwrap();
+        let Jws::Flattened(flattened) = jws else {
+            panic!("expected the flattened serialization");
+        };
+
+        let prot = flattened.signature.protected.as_ref().unwrap();
+        assert_eq!(prot.crit, Some(vec!["b64".to_string()]));
+
+        let compact = flattened.to_compact().unwrap();
+        assert!(compact.contains(".Hello world!."));
+
+        let jws: Jws = compact.parse().unwrap();
+        assert_eq!(
+            verify(&key, &jws, ALGS, &[]).unwrap().payload,
+            b"Hello world!"
+        );
+
+        // A payload containing a period can only be sent detached.
+        let prot = Protected {
+            b64: false,
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let jws = sign(&key, Some(prot), None, b"$.02", OsRng).unwrap();
+        let Jws::Flattened(mut flattened) = jws else {
+            panic!("expected the flattened serialization");
+        };
+        assert!(matches!(
+            flattened.to_compact(),
+            Err(CompactError::Unencoded)
+        ));
+
+        // Display falls back to the detached form.
+        let detached = flattened.to_string();
+        flattened.payload = None;
+        assert_eq!(flattened.to_compact().unwrap(), detached);
+        let jws: Jws = detached.parse().unwrap();
+        verify_detached(&key, &jws, b"$.02", ALGS, &[]).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod strict {
+    use jose_b64::base64ct::{Base64UrlUnpadded, Encoding};
+    use jose_jws::{CompactError, Flattened, Limits, Segment};
+
+    fn parse(s: &str) -> Result<Flattened, CompactError> {
+        Flattened::from_compact(s, &Limits::default())
+    }
+
+    fn header(json: &str) -> String {
+        Base64UrlUnpadded::encode_string(json.as_bytes())
+    }
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.1
+    #[test]
+    fn valid() {
+        let jws = "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9\
+            .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+            .dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+        let flattened = parse(jws).unwrap();
+        assert!(flattened.signature.header.is_none());
+        assert_eq!(flattened.to_string(), jws);
+    }
+
+    #[test]
+    fn limits() {
+        let limits = Limits {
+            token: 8,
+            header: 3,
+        };
+        assert!(matches!(
+            Flattened::from_compact("e30.e30.e30", &limits),
+            Err(CompactError::TokenTooLarge)
+        ));
+
+        let limits = Limits {
+            token: 16,
+            header: 2,
+        };
+        assert!(matches!(
+            Flattened::from_compact("e30.e30.e30", &limits),
+            Err(CompactError::HeaderTooLarge)
+        ));
+    }
+
+    #[test]
+    fn segments() {
+        for jws in ["e30", "e30.e30", "e30.e30.e30.e30", "e30.e30.e30.e30.e30"] {
+            assert!(matches!(parse(jws), Err(CompactError::Segments)));
+        }
+    }
+
+    #[test]
+    fn base64() {
+        for (jws, seg) in [
+            ("e31.e30.e30", Segment::Protected),
+            ("e30.QR.e30", Segment::Payload),
+            ("e30.e30.QQ==", Segment::Signature),
+            ("e30.e3+.e30", Segment::Payload),
+        ] {
+            match parse(jws) {
+                Err(CompactError::Base64(x)) => assert_eq!(x, seg),
+                x => panic!("unexpected result for {jws}: {x:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate() {
+        let prot = header(r#"{"alg":"HS256","alg":"none"}"#);
+        match parse(&format!("{prot}.e30.e30")) {
+            Err(CompactError::Duplicate(name)) => assert_eq!(name, "alg"),
+            x => panic!("unexpected result: {x:?}"),
+        }
+
+        let prot = header(r#"{"jwk":{"kty":"oct","k":"AA","k":"AQ"}}"#);
+        match parse(&format!("{prot}.e30.e30")) {
+            Err(CompactError::Duplicate(name)) => assert_eq!(name, "k"),
+            x => panic!("unexpected result: {x:?}"),
+        }
+
+        let prot = header(r#"{"alg":"HS256""#);
+        assert!(matches!(
+            parse(&format!("{prot}.e30.e30")),
+            Err(CompactError::Header(..))
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "x509", feature = "p256"))]
+mod x509 {
+    use core::time::Duration;
+
+    use jose_b64::base64ct::{Base64, Encoding};
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::Key;
+    use jose_jwk::pkcs8::{DecodePrivateKey, EncodePublicKey};
+    use jose_jwk::Jwk;
+    use jose_jws::crypto::{sign, verify, Error, TrustAnchors};
+    use jose_jws::x509_cert::der::Decode;
+    use jose_jws::x509_cert::Certificate;
+    use jose_jws::{Protected, Unprotected};
+    use rand_core::OsRng;
+
+    // Generated with OpenSSL: P-256 keys, valid from 2024-01-01 to 2124-01-01.
+    const ROOT: &str = "MIIBZDCCAQqgAwIBAgIUU4SiQMNk8WJVICZhOl8rLZdvr6AwCgYIKoZIzj0EAwIwDzENMAsGA1UEAwwEUm9vdDAgFw0yNDAxMDEwMDAwMDBaGA8yMTI0MDEwMTAwMDAwMFowDzENMAsGA1UEAwwEUm9vdDBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABKtBXULuGXvXFnzvoFE+7zUN48rtz9WBwYKMFbjzLMIt6kfGdvaRVxJzGbdoZXlc8Z1d0Hl4tTAJQ3CcqhA68fmjQjBAMA8GA1UdEwEB/wQFMAMBAf8wDgYDVR0PAQH/BAQDAgEGMB0GA1UdDgQWBBQl3GluVVMvjp5uCBB96sFLkaaENDAKBggqhkjOPQQDAgNIADBFAiAWTimsN5lDIrzQs/PFYJcsidGtKHjiZkaF6djtig9y2wIhAOUZ+voVb+c18LBwZZF4WUiDs32bqtqtn01M9XGz+oNX";
+
+    // A different root with the same name.
+    const OTHER: &str = "MIIBYzCCAQqgAwIBAgIUDG6C8yA4Wf+9Vs3uAM1lrkNQSHUwCgYIKoZIzj0EAwIwDzENMAsGA1UEAwwEUm9vdDAgFw0yNDAxMDEwMDAwMDBaGA8yMTI0MDEwMTAwMDAwMFowDzENMAsGA1UEAwwEUm9vdDBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABPbozdS/fxNYL1lGHeVFsqDaxrN9CyloTqeNjWYIEMvWUMM7ktxmDb2q1jySCvL5C2J8/wkoUz1lAO2oveDcmhCjQjBAMA8GA1UdEwEB/wQFMAMBAf8wDgYDVR0PAQH/BAQDAgEGMB0GA1UdDgQWBBRBO8oSOCS0aEOo1aN+aT9pJqo+8DAKBggqhkjOPQQDAgNHADBEAiA7HqmLkxqiRigzlh/PY4VpJjGp6rwcNbFw6LbJd4UBOAIgGVB29wzyzI0UBbSXZm5tp33Xs7nFMoMoWEdrl/jcyqo=";
+
+    const INTER: &str = "MIIBfzCCASWgAwIBAgIDAZUDMAoGCCqGSM49BAMCMA8xDTALBgNVBAMMBFJvb3QwIBcNMjQwMTAxMDAwMDAwWhgPMjEyNDAxMDEwMDAwMDBaMBcxFTATBgNVBAMMDEludGVybWVkaWF0ZTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABKMFC1aAwaPRwCJTyzI3xh8jOUCFQsgaS+HeqYdIuNjjoOtv9TPAdY+gkZ4Lal93QniyRlLBQTuH6sowfPFQ4RijZjBkMBIGA1UdEwEB/wQIMAYBAf8CAQAwDgYDVR0PAQH/BAQDAgEGMB0GA1UdDgQWBBT+/z9C6Li4UzlnCJz/lc1W3al6pjAfBgNVHSMEGDAWgBQl3GluVVMvjp5uCBB96sFLkaaENDAKBggqhkjOPQQDAgNIADBFAiB7HzAEXsZ/E1jD9g9BI1zG3KytWqcueaaTOB+1487oHgIhAIi6QmHb2F5loOBPg+Edh8aqt5SAPGaIjEfKXQJUuL1X";
+
+    const LEAF: &str = "MIIBeTCCAR+gAwIBAgIDAlIxMAoGCCqGSM49BAMCMBcxFTATBgNVBAMMDEludGVybWVkaWF0ZTAgFw0yNDAxMDEwMDAwMDBaGA8yMTI0MDEwMTAwMDAwMFowDzENMAsGA1UEAwwETGVhZjBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABMGcr+oZXMNJ2uilYzYQuxqv+wlJFIkO8TCU7V1zMm5aiB9BcMvwer3ZOUsM6JglwFSJ7kIjkCVpHWWnmc26NwWjYDBeMAwGA1UdEwEB/wQCMAAwDgYDVR0PAQH/BAQDAgeAMB0GA1UdDgQWBBT2N89bxgGzz0YnKNWxP3JWi4ku/DAfBgNVHSMEGDAWgBT+/z9C6Li4UzlnCJz/lc1W3al6pjAKBggqhkjOPQQDAgNIADBFAiEA6yXyq56qB2l49IdDqfk9RfHEN1I2J2Ixy3dKxKeAgLkCID5QqUbAmDhYgPVlFE5i61ZmMxZZlIWThV9b/5Gp82Va";
+
+    // The PKCS#8 private key of the leaf certificate.
+    const LEAF_KEY: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgItlC5DVfJtWtfOm4jvsMqcxxMXj9FM2JsmpvnF2RK+OhRANCAATBnK/qGVzDSdropWM2ELsar/sJSRSJDvEwlO1dczJuWogfQXDL8Hq92TlLDOiYJcBUie5CI5AlaR1lp5nNujcF";
+
+    // 2026-01-01T00:00:00Z
+    const NOW: Duration = Duration::from_secs(1_767_225_600);
+
+    fn der(b64: &str) -> Vec<u8> {
+        Base64::decode_vec(b64).unwrap()
+    }
+
+    fn anchors(root: &str, time: Duration) -> TrustAnchors {
+        TrustAnchors::new(vec![Certificate::from_der(&der(root)).unwrap()], time)
+    }
+
+    fn leaf() -> Jwk {
+        let mut jwk = Jwk::from_pkcs8_der(&der(LEAF_KEY)).unwrap();
+        jwk.prm.x5c = Some(vec![der(LEAF).into(), der(INTER).into()]);
+        jwk
+    }
+
+    #[test]
+    fn validate() {
+        let anchors = anchors(ROOT, NOW);
+        let key = anchors.validate(&[der(LEAF), der(INTER)]).unwrap();
+        assert_eq!(
+            key.to_public_key_der().unwrap().as_bytes(),
+            leaf().key.to_public_key_der().unwrap().as_bytes()
+        );
+
+        // The root may also be included.
+        anchors
+            .validate(&[der(LEAF), der(INTER), der(ROOT)])
+            .unwrap();
+
+        // The intermediate is missing.
+        assert_eq!(anchors.validate(&[der(LEAF)]), Err(Error::Certificate));
+
+        // The order is reversed.
+        assert_eq!(
+            anchors.validate(&[der(INTER), der(LEAF)]),
+            Err(Error::Certificate)
+        );
+
+        // The leaf is not a CA.
+        assert_eq!(
+            anchors.validate(&[der(INTER), der(LEAF), der(LEAF)]),
+            Err(Error::Certificate)
+        );
+
+        let empty: [Vec<u8>; 0] = [];
+        assert_eq!(anchors.validate(&empty), Err(Error::Certificate));
+        assert_eq!(anchors.validate(&[b"garbage"]), Err(Error::Certificate));
+    }
+
+    #[test]
+    fn untrusted() {
+        let anchors = anchors(OTHER, NOW);
+        assert_eq!(
+            anchors.validate(&[der(LEAF), der(INTER)]),
+            Err(Error::Certificate)
+        );
+    }
+
+    #[test]
+    fn expired() {
+        let chain = [der(LEAF), der(INTER)];
+
+        let early = anchors(ROOT, Duration::from_secs(1_672_531_200)); // 2023
+        assert_eq!(early.validate(&chain), Err(Error::Certificate));
+
+        let late = anchors(ROOT, Duration::from_secs(4_891_363_200)); // 2125
+        assert_eq!(late.validate(&chain), Err(Error::Certificate));
+    }
+
+    /// The chain must match the key, as required by
+    /// https://datatracker.ietf.org/doc/html/rfc7517#section-4.7
+    #[test]
+    fn bind() {
+        let anchors = anchors(ROOT, NOW);
+        anchors.bind(&leaf()).unwrap();
+
+        let mut other = leaf();
+        other.key = (&Key::from(p256::SecretKey::random(&mut OsRng))).into();
+        assert_eq!(anchors.bind(&other), Err(Error::Certificate));
+
+        other.prm.x5c = None;
+        assert_eq!(anchors.bind(&other), Err(Error::Certificate));
+    }
+
+    #[test]
+    fn jws() {
+        let jwk = leaf();
+        let key = Key::try_from(&jwk.key).unwrap();
+        let prot = Protected {
+            oth: Unprotected {
+                x5c: jwk.prm.x5c.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let jws = sign(&key, Some(prot), None, b"Hello world!", OsRng).unwrap();
+        let trusted = anchors(ROOT, NOW);
+        let verified = verify(&trusted, &jws, &[Signing::Es256], &[]).unwrap();
+        assert_eq!(verified.payload, b"Hello world!");
+
+        let other = anchors(OTHER, NOW);
+        assert_eq!(
+            verify(&other, &jws, &[Signing::Es256], &[]).unwrap_err(),
+            Error::Certificate
+        );
+
+        let jws = sign(&key, None, None, b"Hello world!", OsRng).unwrap();
+        assert_eq!(
+            verify(&trusted, &jws, &[Signing::Es256], &[]).unwrap_err(),
+            Error::NoKey
+        );
+    }
+}
+
+#[cfg(test)]
+mod x5t {
+    use jose_b64::base64ct::{Base64, Encoding};
+    use jose_jws::{Flattened, Protected, Unprotected};
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7517#appendix-B
+    const CERT: &str = "MIIDQjCCAiqgAwIBAgIGATz/FuLiMA0GCSqGSIb3DQEBBQUAMGIxCzAJBgNVBAYTAlVTMQswCQYDVQQIEwJDTzEPMA0GA1UEBxMGRGVudmVyMRwwGgYDVQQKExNQaW5nIElkZW50aXR5IENvcnAuMRcwFQYDVQQDEw5CcmlhbiBDYW1wYmVsbDAeFw0xMzAyMjEyMzI5MTVaFw0xODA4MTQyMjI5MTVaMGIxCzAJBgNVBAYTAlVTMQswCQYDVQQIEwJDTzEPMA0GA1UEBxMGRGVudmVyMRwwGgYDVQQKExNQaW5nIElkZW50aXR5IENvcnAuMRcwFQYDVQQDEw5CcmlhbiBDYW1wYmVsbDCCASIwDQYJKoZIhvcNAQEBBQADggEPADCCAQoCggEBAL64zn8/QnHYMeZ0LncoXaEde1fiLm1jHjmQsF/449IYALM9if6amFtPDy2yvz3YlRij66s5gyLCyO7ANuVRJx1NbgizcAblIgjtdf/u3WG7K+IiZhtELto/A7Fck9Ws6SQvzRvOE8uSirYbgmj6He4iO8NCyvaK0jIQRMMGQwsU1quGmFgHIXPLfnpnfajr1rVTAwtgV5LEZ4Iel+W1GC8ugMhyr4/p1MtcIM42EA8BzE6ZQqC7VPqPvEjZ2dbZkaBhPbiZAS3YeYBRDWm1p1OZtWamT3cEvqqPpnjL1XyW+oyVVkaZdklLQp2Btgt9qr21m42f4wTw+Xrp6rCKNb0CAwEAATANBgkqhkiG9w0BAQUFAAOCAQEAh8zGlfSlcI0o3rYDPBB07aXNswb4ECNIKG0CETTUxmXl9KUL+9gGlqCz5iWLOgWsnrcKcY0vXPG9J1r9AqBNTqNgHq2G03X09266X5CpOe1zFo+Owb1zxtp3PehFdfQJ610CDLEaS9V9Rqp17hCyybEpOGVwe8fnk+fbEL2Bo3UPGrpsHzUoaGpDftmWssZkhpBJKVMJyf/RuP2SmmaIzmnw9JiSlYhzo4tpzd5rFXhjRbg4zW9C+2qok+2+qDM1iJ684gPHMIY8aLWrdgQTxkumGmTqgawR+N5MDtdPTEQ0XfIBc2cJEUyMTY5MPvACWpkA6SdS4xSvdXK3IVfOWA==";
+
+    const S256: &str = "pJm2BBpkB8y7tCqrWM0X37WOmQTO8zQw-VpxVgBb21I";
+
+    #[test]
+    fn compute() {
+        let mut head = Unprotected {
+            x5c: Some(vec![Base64::decode_vec(CERT).unwrap().into()]),
+            ..Default::default()
+        };
+
+        head.set_x5t();
+        let val = serde_json::to_value(&head).unwrap();
+        assert_eq!(val["x5t#S256"], S256);
+        assert!(head.x5t.matches(head.x5c.as_ref().unwrap()));
+
+        let parsed: Unprotected = serde_json::from_value(val).unwrap();
+        assert_eq!(parsed.x5t, head.x5t);
+    }
+
+    #[test]
+    fn mismatch() {
+        let other = "A".repeat(43);
+        let head = serde_json::json!({ "x5c": [CERT], "x5t#S256": other });
+        serde_json::from_value::<Unprotected>(head.clone()).unwrap_err();
+        serde_json::from_value::<Protected>(head.clone()).unwrap_err();
+
+        let jws = serde_json::json!({ "header": head, "signature": "" });
+        serde_json::from_value::<Flattened>(jws).unwrap_err();
+    }
+}
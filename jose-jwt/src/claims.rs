@@ -0,0 +1,147 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, slice};
+
+use serde::de::{Error, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// The JWT Claims Set
+///
+/// The registered claims are described in RFC 7519 Section 4.1. Any other
+/// (i.e. public or private) claims are kept in `oth`, which is a JSON object
+/// by default but may be any type that can be flattened by `serde`.
+///
+/// Times are expressed as a number of seconds since the UNIX epoch. RFC 7519
+/// Section 2 allows fractional seconds, which are truncated.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Claims<T = Map<String, Value>> {
+    /// RFC 7519 Section 4.1.1
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iss: Option<String>,
+
+    /// RFC 7519 Section 4.1.2
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sub: Option<String>,
+
+    /// RFC 7519 Section 4.1.3
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub aud: Option<Audience>,
+
+    /// RFC 7519 Section 4.1.4
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "numeric_date"
+    )]
+    pub exp: Option<u64>,
+
+    /// RFC 7519 Section 4.1.5
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "numeric_date"
+    )]
+    pub nbf: Option<u64>,
+
+    /// RFC 7519 Section 4.1.6
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        deserialize_with = "numeric_date"
+    )]
+    pub iat: Option<u64>,
+
+    /// RFC 7519 Section 4.1.7
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub jti: Option<String>,
+
+    /// Other claims that may appear in the claims set.
+    #[serde(flatten)]
+    pub oth: T,
+}
+
+/// A NumericDate, which may have fractional seconds (RFC 7519 Section 2).
+struct NumericDate(u64);
+
+impl<'de> Deserialize<'de> for NumericDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NumericDateVisitor;
+
+        impl Visitor<'_> for NumericDateVisitor {
+            type Value = NumericDate;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a non-negative number of seconds")
+            }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(NumericDate(v))
+            }
+
+            fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+                u64::try_from(v)
+                    .map(NumericDate)
+                    .map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+            }
+
+            fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+                // Casting truncates the fractional seconds.
+                match v >= 0.0 && v < u64::MAX as f64 {
+                    true => Ok(NumericDate(v as u64)),
+                    false => Err(E::invalid_value(Unexpected::Float(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(NumericDateVisitor)
+    }
+}
+
+fn numeric_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Ok(Option::<NumericDate>::deserialize(deserializer)?.map(|x| x.0))
+}
+
+/// The intended recipients of a JWT
+///
+/// RFC 7519 Section 4.1.3 allows either a single string or an array of
+/// strings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+    /// A single recipient.
+    One(String),
+
+    /// Multiple recipients.
+    Many(Vec<String>),
+}
+
+impl Audience {
+    /// Iterates over the recipients.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        match self {
+            Self::One(one) => slice::from_ref(one).iter(),
+            Self::Many(many) => many.iter(),
+        }
+        .map(|x| x.as_str())
+    }
+
+    /// Tests whether the recipient is one of the intended recipients.
+    pub fn contains(&self, aud: &str) -> bool {
+        self.iter().any(|x| x == aud)
+    }
+}
+
+impl From<String> for Audience {
+    fn from(value: String) -> Self {
+        Self::One(value)
+    }
+}
+
+impl From<Vec<String>> for Audience {
+    fn from(value: Vec<String>) -> Self {
+        Self::Many(value)
+    }
+}
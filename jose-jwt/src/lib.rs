@@ -1,3 +1,6 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
 #![no_std]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![doc = include_str!("../README.md")]
@@ -15,3 +18,107 @@
     unused_lifetimes,
     unused_qualifications
 )]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+mod claims;
+mod validate;
+
+pub use claims::{Audience, Claims};
+pub use validate::{Clock, Validator};
+
+#[cfg(feature = "std")]
+pub use validate::SystemClock;
+
+use alloc::vec::Vec;
+
+use jose_jwa::Signing;
+use jose_jws::crypto::{Verifier, VerifyingKey};
+use jose_jws::{Jws, Signature};
+use serde::de::DeserializeOwned;
+
+/// An error that occurred while processing a JWT.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The JWT is malformed.
+    Invalid,
+
+    /// The signature could not be verified.
+    Crypto(jose_jws::crypto::Error),
+
+    /// The JWT has expired (`exp`).
+    Expired,
+
+    /// The JWT is not yet valid (`nbf`).
+    Immature,
+
+    /// The issuer (`iss`) is not the expected one.
+    Issuer,
+
+    /// The validator is not one of the intended recipients (`aud`).
+    Audience,
+
+    /// A required claim is missing.
+    Missing,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("malformed JWT"),
+            Self::Crypto(e) => write!(f, "signature verification failed: {e}"),
+            Self::Expired => f.write_str("JWT has expired"),
+            Self::Immature => f.write_str("JWT is not yet valid"),
+            Self::Issuer => f.write_str("unexpected issuer"),
+            Self::Audience => f.write_str("not an intended audience"),
+            Self::Missing => f.write_str("required claim is missing"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Crypto(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<jose_jws::crypto::Error> for Error {
+    fn from(value: jose_jws::crypto::Error) -> Self {
+        Self::Crypto(value)
+    }
+}
+
+/// Parses a compact JWT, verifies its signature and validates its claims.
+///
+/// Only signatures using one of `algs` are accepted (see
+/// [`jose_jws::crypto::allowed`]). The verified claims are returned when all
+/// checks succeed.
+pub fn verify<K, T>(
+    key: &K,
+    token: &str,
+    algs: &[Signing],
+    validator: &Validator,
+    clock: &impl Clock,
+) -> Result<Claims<T>, Error>
+where
+    K: for<'a> VerifyingKey<'a, &'a Signature> + ?Sized,
+    for<'a> <K as VerifyingKey<'a, &'a Signature>>::StartError: From<<<K as VerifyingKey<'a, &'a Signature>>::Verifier as Verifier<'a>>::FinishError>
+        + From<jose_jws::crypto::Error>
+        + From<Vec<<K as VerifyingKey<'a, &'a Signature>>::StartError>>
+        + Into<Error>,
+    T: DeserializeOwned,
+{
+    let jws: Jws = token.parse().map_err(|_| Error::Invalid)?;
+    let verified = jose_jws::crypto::verify(key, &jws, algs, &[]).map_err(Into::into)?;
+    let claims: Claims<T> = serde_json::from_slice(verified.payload).map_err(|_| Error::Invalid)?;
+    validator.validate(&claims, clock)?;
+    Ok(claims)
+}
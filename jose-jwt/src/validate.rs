@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use alloc::string::String;
+
+use crate::{Claims, Error};
+
+/// A source of the current time
+pub trait Clock {
+    /// Returns the number of seconds since the UNIX epoch.
+    fn now(&self) -> u64;
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    fn now(&self) -> u64 {
+        self()
+    }
+}
+
+/// The system clock
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+/// Validation of the registered claims
+///
+/// The `exp` and `nbf` claims are always checked when present. The other
+/// checks are configured by the fields below.
+#[derive(Clone, Debug, Default)]
+pub struct Validator {
+    /// The number of seconds of clock skew to tolerate.
+    pub leeway: u64,
+
+    /// Whether the `exp` claim must be present.
+    pub require_exp: bool,
+
+    /// The expected issuer.
+    pub iss: Option<String>,
+
+    /// The audience with which the validator identifies.
+    ///
+    /// As required by RFC 7519 Section 4.1.3, a JWT with an `aud` claim is
+    /// rejected unless it contains this value. A JWT without an `aud` claim
+    /// is only accepted if this is `None`.
+    pub aud: Option<String>,
+}
+
+impl Validator {
+    /// Validates the claims against the current time.
+    pub fn validate<T>(&self, claims: &Claims<T>, clock: &impl Clock) -> Result<(), Error> {
+        let now = clock.now();
+
+        match claims.exp {
+            Some(exp) if now >= exp.saturating_add(self.leeway) => return Err(Error::Expired),
+            None if self.require_exp => return Err(Error::Missing),
+            _ => (),
+        }
+
+        if let Some(nbf) = claims.nbf {
+            if now.saturating_add(self.leeway) < nbf {
+                return Err(Error::Immature);
+            }
+        }
+
+        if let Some(iss) = self.iss.as_deref() {
+            if claims.iss.as_deref() != Some(iss) {
+                return Err(Error::Issuer);
+            }
+        }
+
+        match (self.aud.as_deref(), claims.aud.as_ref()) {
+            (Some(aud), Some(auds)) if auds.contains(aud) => (),
+            (None, None) => (),
+            _ => return Err(Error::Audience),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2022 Profian Inc. <opensource@profian.com>
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use jose_jwt::{Audience, Claims, Error, Validator};
+
+/// From https://datatracker.ietf.org/doc/html/rfc7519#section-3.1
+#[test]
+fn claims() {
+    let val = serde_json::json!({
+        "iss": "joe",
+        "exp": 1300819380,
+        "http://example.com/is_root": true
+    });
+
+    let claims: Claims = serde_json::from_value(val.clone()).unwrap();
+    assert_eq!(claims.iss.as_deref(), Some("joe"));
+    assert_eq!(claims.exp, Some(1300819380));
+    assert_eq!(claims.oth["http://example.com/is_root"], true);
+    assert_eq!(val, serde_json::to_value(&claims).unwrap());
+}
+
+/// NumericDate values may have fractional seconds (RFC 7519 Section 2).
+#[test]
+fn fractional() {
+    let val = serde_json::json!({ "exp": 1700000000.5, "nbf": 1699999999.9, "iat": 0.0 });
+    let claims: Claims = serde_json::from_value(val).unwrap();
+    assert_eq!(claims.exp, Some(1700000000));
+    assert_eq!(claims.nbf, Some(1699999999));
+    assert_eq!(claims.iat, Some(0));
+
+    let val = serde_json::json!({ "exp": null });
+    let claims: Claims = serde_json::from_value(val).unwrap();
+    assert_eq!(claims.exp, None);
+
+    for exp in [
+        serde_json::json!(-1),
+        serde_json::json!(-0.5),
+        serde_json::json!("1"),
+    ] {
+        let val = serde_json::json!({ "exp": exp });
+        assert!(serde_json::from_value::<Claims>(val).is_err());
+    }
+}
+
+#[test]
+fn private() {
+    #[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Private {
+        name: String,
+        admin: bool,
+    }
+
+    let val = serde_json::json!({ "sub": "1234567890", "name": "John Doe", "admin": true });
+    let claims: Claims<Private> = serde_json::from_value(val.clone()).unwrap();
+    assert_eq!(claims.sub.as_deref(), Some("1234567890"));
+    assert_eq!(claims.oth.name, "John Doe");
+    assert!(claims.oth.admin);
+    assert_eq!(val, serde_json::to_value(&claims).unwrap());
+}
+
+#[test]
+fn audience() {
+    let one: Audience = serde_json::from_str(r#""a""#).unwrap();
+    assert_eq!(one, Audience::One("a".into()));
+    assert!(one.contains("a"));
+    assert!(!one.contains("b"));
+
+    let many: Audience = serde_json::from_str(r#"["a","b"]"#).unwrap();
+    assert_eq!(many, Audience::Many(vec!["a".into(), "b".into()]));
+    assert!(many.contains("b"));
+    assert_eq!(many.iter().collect::<Vec<_>>(), ["a", "b"]);
+}
+
+#[test]
+fn validate() {
+    let claims = Claims {
+        iss: Some("joe".into()),
+        aud: Some(Audience::Many(vec!["a".into(), "b".into()])),
+        nbf: Some(100),
+        exp: Some(200),
+        ..Claims::<()>::default()
+    };
+
+    let validator = Validator {
+        iss: Some("joe".into()),
+        aud: Some("b".into()),
+        ..Default::default()
+    };
+
+    assert_eq!(validator.validate(&claims, &|| 100), Ok(()));
+    assert_eq!(validator.validate(&claims, &|| 199), Ok(()));
+    assert_eq!(validator.validate(&claims, &|| 99), Err(Error::Immature));
+    assert_eq!(validator.validate(&claims, &|| 200), Err(Error::Expired));
+
+    let leeway = Validator {
+        leeway: 10,
+        ..validator.clone()
+    };
+    assert_eq!(leeway.validate(&claims, &|| 90), Ok(()));
+    assert_eq!(leeway.validate(&claims, &|| 209), Ok(()));
+    assert_eq!(leeway.validate(&claims, &|| 210), Err(Error::Expired));
+
+    let issuer = Validator {
+        iss: Some("bob".into()),
+        ..validator.clone()
+    };
+    assert_eq!(issuer.validate(&claims, &|| 150), Err(Error::Issuer));
+
+    // A JWT with an audience is only accepted by one of its recipients.
+    let audience = Validator {
+        aud: Some("c".into()),
+        ..validator.clone()
+    };
+    assert_eq!(audience.validate(&claims, &|| 150), Err(Error::Audience));
+
+    let audience = Validator {
+        aud: None,
+        ..validator.clone()
+    };
+    assert_eq!(audience.validate(&claims, &|| 150), Err(Error::Audience));
+
+    let claims = Claims::<()>::default();
+    assert_eq!(Validator::default().validate(&claims, &|| 0), Ok(()));
+
+    let required = Validator {
+        require_exp: true,
+        ..Default::default()
+    };
+    assert_eq!(required.validate(&claims, &|| 0), Err(Error::Missing));
+}
+
+#[cfg(all(test, feature = "crypto"))]
+mod verify {
+    use jose_jwa::Signing;
+    use jose_jwk::crypto::Key;
+    use jose_jwk::Jwk;
+    use jose_jws::crypto;
+    use jose_jws::{Jws, Protected, Unprotected};
+    use jose_jwt::{verify, Claims, Error, Validator};
+    use rand_core::OsRng;
+
+    /// From https://datatracker.ietf.org/doc/html/rfc7515#appendix-A.1
+    const JWT: &str = "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9\
+        .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+        .dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    fn key() -> Key {
+        let jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "oct",
+            "k": "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"
+        }))
+        .unwrap();
+
+        Key::try_from(&jwk.key).unwrap()
+    }
+
+    const HS256: &[Signing] = &[Signing::Hs256];
+
+    #[test]
+    fn rfc7515() {
+        let validator = Validator {
+            iss: Some("joe".into()),
+            ..Default::default()
+        };
+
+        let claims: Claims = verify(&key(), JWT, HS256, &validator, &|| 1300819379).unwrap();
+        assert_eq!(claims.iss.as_deref(), Some("joe"));
+        assert_eq!(claims.oth["http://example.com/is_root"], true);
+
+        let err = verify::<_, Claims>(&key(), JWT, HS256, &validator, &|| 1300819380).unwrap_err();
+        assert_eq!(err, Error::Expired);
+
+        let other = Key::Oct(vec![0u8; 32].into_boxed_slice().into());
+        let err = verify::<_, Claims>(&other, JWT, HS256, &validator, &|| 0).unwrap_err();
+        assert_eq!(err, Error::Crypto(crypto::Error::Signature));
+        assert_eq!(
+            err.to_string(),
+            "signature verification failed: signature mismatch"
+        );
+
+        let rs256 = &[Signing::Rs256];
+        let err = verify::<_, Claims>(&key(), JWT, rs256, &validator, &|| 0).unwrap_err();
+        assert_eq!(err, Error::Crypto(crypto::Error::Policy));
+
+        let err = verify::<_, Claims>(&key(), "e30.e30", HS256, &validator, &|| 0).unwrap_err();
+        assert_eq!(err, Error::Invalid);
+    }
+
+    fn sign(payload: &[u8]) -> String {
+        let prot = Protected {
+            oth: Unprotected {
+                alg: Some(Signing::Hs256),
+                typ: Some("JWT".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        match jose_jws::crypto::sign(&key(), Some(prot), None, payload, OsRng).unwrap() {
+            Jws::Flattened(jws) => jws.to_string(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let claims = Claims {
+            sub: Some("ferris".into()),
+            exp: Some(1000),
+            ..Claims::<()>::default()
+        };
+
+        let token = sign(&serde_json::to_vec(&claims).unwrap());
+        let verified = verify(&key(), &token, HS256, &Validator::default(), &|| 999).unwrap();
+        assert_eq!(claims, verified);
+
+        // The payload must be a JSON object.
+        let token = sign(b"[]");
+        let err = verify::<_, Claims>(&key(), &token, HS256, &Validator::default(), &|| 0);
+        assert_eq!(err.unwrap_err(), Error::Invalid);
+    }
+}